@@ -0,0 +1,156 @@
+use crate::{Error, Height, MapPattern, MAP_SIZE};
+
+/// What happens when an operation produces a height outside -50..=50.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// the height is clamped to -50 or 50
+    #[default]
+    Clamp,
+    /// the operation fails and the pattern is left unchanged
+    Error,
+    /// the height wraps around, e.g. 51 becomes -50
+    Wrap,
+}
+
+impl OverflowPolicy {
+    pub fn apply(self, level: i32) -> Result<Height, Error> {
+        let min = i64::from(Height::MIN.get());
+        let span = i64::from(Height::MAX.get()) - min + 1;
+        match self {
+            OverflowPolicy::Clamp => Ok(Height::clamped(level)),
+            OverflowPolicy::Error => Height::try_from(level),
+            OverflowPolicy::Wrap => {
+                let wrapped = (i64::from(level) - min).rem_euclid(span) + min;
+                Height::try_from(wrapped as i32)
+            }
+        }
+    }
+}
+
+/// How two patterns are combined height by height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combine {
+    Min,
+    Max,
+    /// rounded to the nearest height
+    Average,
+    /// `self * (1 - weight) + other * weight`, rounded to the nearest height
+    Blend(f32),
+    Add,
+    Subtract,
+}
+
+/// Operations over every height of a pattern.
+/// Results are computed for the whole pattern before anything is written,
+/// so with `OverflowPolicy::Error` a failing operation leaves the pattern untouched.
+impl MapPattern {
+    /// replace every height with `f(height)`, the result is passed through `policy`
+    pub fn map_heights(
+        &mut self,
+        policy: OverflowPolicy,
+        f: impl Fn(i32) -> i32,
+    ) -> Result<(), Error> {
+        let mut result = [Height::ZERO; MAP_SIZE];
+        for (to, from) in result.iter_mut().zip(self.level_map.iter()) {
+            *to = policy.apply(f(i32::from(*from)))?;
+        }
+        self.level_map = result;
+        Ok(())
+    }
+
+    /// raise every height by `amount`
+    pub fn add_height(&mut self, amount: i32, policy: OverflowPolicy) -> Result<(), Error> {
+        self.map_heights(policy, |level| level.saturating_add(amount))
+    }
+
+    /// lower every height by `amount`
+    pub fn sub_height(&mut self, amount: i32, policy: OverflowPolicy) -> Result<(), Error> {
+        self.map_heights(policy, |level| level.saturating_sub(amount))
+    }
+
+    /// stretch heights away from (or towards) `pivot` by `factor`, rounded to the nearest height
+    /// Fails if `factor` is NaN or infinite.
+    pub fn scale_heights(
+        &mut self,
+        pivot: Height,
+        factor: f32,
+        policy: OverflowPolicy,
+    ) -> Result<(), Error> {
+        check_finite("factor", factor)?;
+        let pivot = f32::from(pivot.get());
+        // stay in f32 until the end, the cast saturates instead of overflowing
+        self.map_heights(policy, |level| {
+            (pivot + (level as f32 - pivot) * factor).round() as i32
+        })
+    }
+
+    /// clamp every height into `min..=max`
+    pub fn clamp_heights(&mut self, min: Height, max: Height) {
+        let (min, max) = (min.min(max), min.max(max));
+        for level in self.level_map.iter_mut() {
+            *level = (*level).clamp(min, max);
+        }
+    }
+
+    /// mirror every height around 0, pits become pillars and the other way around
+    pub fn invert_heights(&mut self) {
+        for level in self.level_map.iter_mut() {
+            *level = -*level;
+        }
+    }
+
+    /// linearly remap heights so the lowest cell ends up at `min` and the highest at `max`
+    /// A flat pattern is moved to `min`.
+    pub fn normalize_heights(&mut self, min: Height, max: Height) {
+        let low = i32::from(*self.level_map.iter().min().unwrap());
+        let high = i32::from(*self.level_map.iter().max().unwrap());
+        let (min, max) = (i32::from(min), i32::from(max));
+        let range = (high - low).max(1) as f32;
+        self.map_heights(OverflowPolicy::Clamp, |level| {
+            min + ((level - low) as f32 / range * (max - min) as f32).round() as i32
+        })
+        .expect("clamping cannot fail");
+    }
+
+    /// combine the heights of both patterns cell by cell, prefabs of `self` are kept
+    /// Fails if the weight of `Combine::Blend` is NaN or infinite.
+    pub fn combine_heights(
+        &mut self,
+        other: &MapPattern,
+        combine: Combine,
+        policy: OverflowPolicy,
+    ) -> Result<(), Error> {
+        if let Combine::Blend(weight) = combine {
+            check_finite("blend weight", weight)?;
+        }
+        let mut result = [Height::ZERO; MAP_SIZE];
+        for (index, to) in result.iter_mut().enumerate() {
+            let a = i32::from(self.level_map[index]);
+            let b = i32::from(other.level_map[index]);
+            let level = match combine {
+                Combine::Min => a.min(b),
+                Combine::Max => a.max(b),
+                Combine::Average => ((a + b) as f32 / 2.0).round() as i32,
+                Combine::Blend(weight) => {
+                    (a as f32 * (1.0 - weight) + b as f32 * weight).round() as i32
+                }
+                Combine::Add => a + b,
+                Combine::Subtract => a - b,
+            };
+            *to = policy.apply(level)?;
+        }
+        self.level_map = result;
+        Ok(())
+    }
+}
+
+fn check_finite(name: &str, value: f32) -> Result<(), Error> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::UltraMapInvalidParameter(format!(
+            "{} must be finite, got {}",
+            name, value
+        )))
+    }
+}
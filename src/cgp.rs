@@ -0,0 +1,247 @@
+use std::{fmt, iter::Peekable, str::Chars};
+
+use thiserror::Error;
+
+use crate::{Height, MapPattern, Prefab};
+
+/// Part of a `.cgp` file a parse error occurred in.
+/// The height grid comes first, followed by the prefab grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Heights,
+    Prefabs,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Section::Heights => write!(f, "height grid"),
+            Section::Prefabs => write!(f, "prefab grid"),
+        }
+    }
+}
+
+/// Describes where and why a `.cgp` file could not be parsed.
+/// Line and column are 1 indexed and point at the first character of the offending text.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("line {line}, column {column} ({section}): expected {expected}, found {found}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub section: Section,
+    pub found: String,
+    pub expected: String,
+}
+
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(c) if c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, section: Section, found: impl Into<String>, expected: &str) -> ParseError {
+        self.error_at(self.line, self.column, section, found, expected)
+    }
+
+    fn error_at(
+        &self,
+        line: usize,
+        column: usize,
+        section: Section,
+        found: impl Into<String>,
+        expected: &str,
+    ) -> ParseError {
+        ParseError {
+            line,
+            column,
+            section,
+            found: found.into(),
+            expected: expected.to_string(),
+        }
+    }
+}
+
+const END_OF_INPUT: &str = "end of input";
+
+/// How the cells are laid out when writing a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Layout {
+    /// 16 rows of heights, an empty line and 16 rows of prefabs, ending in a newline.
+    /// This is the `.cgp` file format.
+    Grid,
+    /// All heights on one line and all prefabs on the next.
+    Compact,
+}
+
+/// The one serializer for patterns, every text representation goes through here
+/// so that anything written can be read back by `parse`.
+pub(crate) fn write(pattern: &MapPattern, f: &mut impl fmt::Write, layout: Layout) -> fmt::Result {
+    let row_break = |index: usize| layout == Layout::Grid && index > 0 && index.is_multiple_of(16);
+
+    for (index, level) in pattern.level_map.iter().enumerate() {
+        if row_break(index) {
+            f.write_char('\n')?;
+        }
+        write_height(*level, f)?;
+    }
+
+    match layout {
+        Layout::Grid => f.write_str("\n\n")?,
+        Layout::Compact => f.write_char('\n')?,
+    }
+
+    for (index, prefab) in pattern.prefab_map.iter().enumerate() {
+        if row_break(index) {
+            f.write_char('\n')?;
+        }
+        f.write_char((*prefab).into())?;
+    }
+
+    match layout {
+        Layout::Grid => f.write_char('\n'),
+        Layout::Compact => Ok(()),
+    }
+}
+
+/// Single digits are written as is, everything else is wrapped in parentheses.
+fn write_height(level: Height, f: &mut impl fmt::Write) -> fmt::Result {
+    if (0..=9).contains(&level.get()) {
+        write!(f, "{}", level)
+    } else {
+        write!(f, "({})", level)
+    }
+}
+
+/// Parses the text of a `.cgp` file.
+/// Whitespace between cells is ignored, heights that don't fit in a single digit are wrapped in parentheses.
+pub(crate) fn parse(input: &str) -> Result<MapPattern, ParseError> {
+    let mut cursor = Cursor::new(input);
+    let mut pattern = MapPattern::default();
+
+    for level in pattern.level_map.iter_mut() {
+        *level = parse_height(&mut cursor)?;
+    }
+
+    for prefab in pattern.prefab_map.iter_mut() {
+        cursor.skip_whitespace();
+        let c = match cursor.peek() {
+            Some(c) => c,
+            None => return Err(cursor.error(Section::Prefabs, END_OF_INPUT, "a prefab character")),
+        };
+        *prefab = Prefab::try_from(c).map_err(|_| {
+            cursor.error(
+                Section::Prefabs,
+                format!("`{}`", c),
+                "a prefab character (0, n, p, J, s, H)",
+            )
+        })?;
+        cursor.next();
+    }
+
+    cursor.skip_whitespace();
+    if let Some(c) = cursor.peek() {
+        return Err(cursor.error(Section::Prefabs, format!("`{}`", c), END_OF_INPUT));
+    }
+
+    Ok(pattern)
+}
+
+/// Parses a `.cgp` file that hasn't been decoded yet.
+pub(crate) fn parse_bytes(bytes: &[u8]) -> Result<MapPattern, ParseError> {
+    match std::str::from_utf8(bytes) {
+        Ok(input) => parse(input),
+        Err(e) => {
+            // Parsing the valid prefix either fails earlier on its own or
+            // runs out of input exactly where the invalid byte starts.
+            let valid = std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default();
+            let mut error = match parse(valid) {
+                Err(error) if error.found != END_OF_INPUT => return Err(error),
+                Err(error) => error,
+                Ok(_) => {
+                    let mut cursor = Cursor::new(valid);
+                    while cursor.next().is_some() {}
+                    cursor.error(Section::Prefabs, "", END_OF_INPUT)
+                }
+            };
+            error.found = "invalid UTF-8".to_string();
+            Err(error)
+        }
+    }
+}
+
+fn parse_height(cursor: &mut Cursor) -> Result<Height, ParseError> {
+    const EXPECTED: &str = "a digit or a parenthesized height like (-12)";
+    const EXPECTED_RANGE: &str = "a height between -50 and 50";
+
+    cursor.skip_whitespace();
+    let (line, column) = (cursor.line, cursor.column);
+    let c = match cursor.next() {
+        Some(c) => c,
+        None => return Err(cursor.error(Section::Heights, END_OF_INPUT, EXPECTED)),
+    };
+
+    if let Some(digit) = c.to_digit(10) {
+        return Ok(Height::new(digit as i8).expect("single digits are valid heights"));
+    }
+
+    if c != '(' {
+        return Err(cursor.error_at(line, column, Section::Heights, format!("`{}`", c), EXPECTED));
+    }
+
+    let mut temp = String::new();
+    loop {
+        cursor.skip_whitespace();
+        match cursor.next() {
+            Some(')') => break,
+            Some(c) => temp.push(c),
+            None => return Err(cursor.error(Section::Heights, END_OF_INPUT, "`)`")),
+        }
+    }
+
+    let expected = match temp.parse::<i8>() {
+        Ok(level) => match Height::new(level) {
+            Ok(level) => return Ok(level),
+            Err(_) => EXPECTED_RANGE,
+        },
+        // numbers too large for an i8 are still numbers, just out of range
+        Err(_) if temp.parse::<i64>().is_ok() => EXPECTED_RANGE,
+        Err(_) => EXPECTED,
+    };
+    Err(cursor.error_at(
+        line,
+        column,
+        Section::Heights,
+        format!("`({})`", temp),
+        expected,
+    ))
+}
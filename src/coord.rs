@@ -0,0 +1,94 @@
+use std::fmt;
+
+use crate::{Error, MAP_SIZE};
+
+/// Width and height of a pattern.
+pub const GRID_SIZE: usize = 16;
+
+/// A position on the 16x16 grid, both axes are 0 indexed.
+/// A `Coord` can only be constructed through validating constructors, so it always points inside the grid.
+///
+/// `x` is the column and `y` is the row, matching the `.cgp` text where every line is a row:
+/// `x` counts characters from the left, `y` counts lines from the top.
+/// The cell index is `y * 16 + x`.
+///
+/// Before this convention was settled `set_level_at` and `set_prefab_at` took `(row, column)`,
+/// use `Coord::from_legacy` to convert such coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Coord {
+    x: usize,
+    y: usize,
+}
+
+impl Coord {
+    pub fn new(x: usize, y: usize) -> Result<Self, Error> {
+        if x >= GRID_SIZE || y >= GRID_SIZE {
+            return Err(Error::UltraMapIndexOutOfBounds);
+        }
+        Ok(Self { x, y })
+    }
+
+    /// same as `new`, with the axes spelled out
+    pub fn from_column_row(column: usize, row: usize) -> Result<Self, Error> {
+        Self::new(column, row)
+    }
+
+    /// converts coordinates in the old `(row, column)` order of `set_level_at` and `set_prefab_at`
+    pub fn from_legacy(x: usize, y: usize) -> Result<Self, Error> {
+        Self::new(y, x)
+    }
+
+    /// coordinate of the cell stored at `index` in the level and prefab maps
+    pub fn from_index(index: usize) -> Result<Self, Error> {
+        if index >= MAP_SIZE {
+            return Err(Error::UltraMapIndexOutOfBounds);
+        }
+        Ok(Self {
+            x: index % GRID_SIZE,
+            y: index / GRID_SIZE,
+        })
+    }
+
+    /// the column
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    /// the row
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
+    pub fn column(&self) -> usize {
+        self.x
+    }
+
+    pub fn row(&self) -> usize {
+        self.y
+    }
+
+    /// index of the cell in the level and prefab maps
+    pub fn index(&self) -> usize {
+        self.y * GRID_SIZE + self.x
+    }
+}
+
+impl TryFrom<(usize, usize)> for Coord {
+    type Error = Error;
+
+    fn try_from((x, y): (usize, usize)) -> Result<Self, Self::Error> {
+        Coord::new(x, y)
+    }
+}
+
+impl From<Coord> for (usize, usize) {
+    fn from(coord: Coord) -> Self {
+        (coord.x, coord.y)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
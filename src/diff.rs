@@ -0,0 +1,117 @@
+use std::fmt;
+
+use crate::{Cell, Change, Coord, Error, MapPattern, GRID_SIZE, MAP_SIZE};
+
+/// The cells that differ between two patterns, see `MapPattern::diff`.
+///
+/// Displays as a 16x16 grid: `+` marks raised cells, `-` lowered cells,
+/// `*` cells where only the prefab changed and `.` unchanged cells.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternDiff {
+    changes: Vec<Change>,
+}
+
+/// A cell that didn't match the base of a `PatternDiff` it was patched with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatchConflict {
+    /// what the diff expected the cell to be
+    pub expected: Cell,
+    /// what the cell actually is
+    pub found: Cell,
+}
+
+impl MapPattern {
+    /// what changed going from `self` to `other`
+    pub fn diff(&self, other: &MapPattern) -> PatternDiff {
+        let changes = Coord::all()
+            .map(|at| Change {
+                before: self.cell(at),
+                after: other.cell(at),
+            })
+            .filter(|change| change.before != change.after)
+            .collect();
+        PatternDiff { changes }
+    }
+}
+
+impl PatternDiff {
+    /// changed cells in storage order
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn get(&self, at: Coord) -> Option<&Change> {
+        self.changes.iter().find(|change| change.before.coord == at)
+    }
+
+    /// how much the height at `at` changed, 0 for unchanged cells
+    pub fn height_delta(&self, at: Coord) -> i32 {
+        self.get(at).map_or(0, |change| {
+            i32::from(change.after.height) - i32::from(change.before.height)
+        })
+    }
+
+    /// the diff going the other way
+    pub fn reversed(&self) -> PatternDiff {
+        let changes = self
+            .changes
+            .iter()
+            .map(|change| Change {
+                before: change.after,
+                after: change.before,
+            })
+            .collect();
+        PatternDiff { changes }
+    }
+
+    /// apply the diff as a patch to `target`
+    /// Every changed cell of `target` has to match the base the diff was made from,
+    /// otherwise nothing is changed and all mismatching cells are reported.
+    pub fn apply(&self, target: &mut MapPattern) -> Result<(), Error> {
+        let conflicts: Vec<PatchConflict> = self
+            .changes
+            .iter()
+            .filter(|change| target.cell(change.before.coord) != change.before)
+            .map(|change| PatchConflict {
+                expected: change.before,
+                found: target.cell(change.before.coord),
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(Error::UltraMapPatchConflict(conflicts));
+        }
+
+        for change in &self.changes {
+            let at = change.after.coord;
+            target[at] = change.after.height;
+            target.prefab_map[at.index()] = change.after.prefab;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PatternDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut markers = ['.'; MAP_SIZE];
+        for change in &self.changes {
+            markers[change.before.coord.index()] =
+                match change.after.height.cmp(&change.before.height) {
+                    std::cmp::Ordering::Greater => '+',
+                    std::cmp::Ordering::Less => '-',
+                    std::cmp::Ordering::Equal => '*',
+                };
+        }
+        for row in markers.chunks(GRID_SIZE) {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
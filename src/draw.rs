@@ -0,0 +1,165 @@
+use crate::{Connectivity, Coord, Height, MapPattern, Prefab, Region, GRID_SIZE, MAP_SIZE};
+
+/// What the drawing operations write into each cell, `None` leaves that part of the cell untouched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Paint {
+    pub height: Option<Height>,
+    pub prefab: Option<Prefab>,
+}
+
+impl Paint {
+    pub fn height(height: Height) -> Self {
+        Self {
+            height: Some(height),
+            prefab: None,
+        }
+    }
+
+    pub fn prefab(prefab: Prefab) -> Self {
+        Self {
+            height: None,
+            prefab: Some(prefab),
+        }
+    }
+
+    pub fn both(height: Height, prefab: Prefab) -> Self {
+        Self {
+            height: Some(height),
+            prefab: Some(prefab),
+        }
+    }
+}
+
+/// Which cells a flood fill spreads to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloodMode {
+    /// cells with the same height as the start cell
+    Height,
+    /// cells with the same prefab as the start cell
+    Prefab,
+    /// cells with the same height and prefab as the start cell
+    Both,
+}
+
+/// The drawing operations return the cells they actually changed, in storage order,
+/// cells that already looked like `paint` are left out.
+impl MapPattern {
+    /// paint every given cell
+    pub fn paint(&mut self, cells: impl IntoIterator<Item = Coord>, paint: Paint) -> Vec<Coord> {
+        let mut selected = [false; MAP_SIZE];
+        for at in cells {
+            selected[at.index()] = true;
+        }
+
+        let mut changed = Vec::new();
+        for at in Coord::all().filter(|at| selected[at.index()]) {
+            let before = self.cell(at);
+            if let Some(height) = paint.height {
+                self.level_map[at.index()] = height;
+            }
+            if let Some(prefab) = paint.prefab {
+                self.prefab_map[at.index()] = prefab;
+            }
+            if self.cell(at) != before {
+                changed.push(at);
+            }
+        }
+        changed
+    }
+
+    /// fill the rectangle spanned by two opposite corners, both corners are included
+    pub fn fill_rect(&mut self, corner: Coord, opposite: Coord, paint: Paint) -> Vec<Coord> {
+        self.paint(Region::rect(corner, opposite).cells(), paint)
+    }
+
+    /// draw a line between two cells (Bresenham), both ends are included
+    pub fn draw_line(&mut self, from: Coord, to: Coord, paint: Paint) -> Vec<Coord> {
+        let cells = line(from, to);
+        self.paint(cells, paint)
+    }
+
+    /// fill every cell whose center lies within `radius` cells of `center`
+    pub fn fill_circle(&mut self, center: Coord, radius: usize, paint: Paint) -> Vec<Coord> {
+        let cells = Coord::all().filter(|at| in_circle(center, radius, *at));
+        self.paint(cells, paint)
+    }
+
+    /// draw the outline of the circle `fill_circle` would fill
+    pub fn draw_circle(&mut self, center: Coord, radius: usize, paint: Paint) -> Vec<Coord> {
+        // an outline cell is inside the circle but has a neighbor (or the grid border) outside of it
+        let cells = Coord::all().filter(|at| {
+            in_circle(center, radius, *at)
+                && (at.neighbors(Connectivity::Four).count() < 4
+                    || at
+                        .neighbors(Connectivity::Four)
+                        .any(|neighbor| !in_circle(center, radius, neighbor)))
+        });
+        self.paint(cells, paint)
+    }
+
+    /// fill the area of 4-connected cells around `start` that match it according to `mode`
+    pub fn flood_fill(&mut self, start: Coord, mode: FloodMode, paint: Paint) -> Vec<Coord> {
+        let target = self.cell(start);
+        let matches = |pattern: &MapPattern, at: Coord| {
+            let cell = pattern.cell(at);
+            match mode {
+                FloodMode::Height => cell.height == target.height,
+                FloodMode::Prefab => cell.prefab == target.prefab,
+                FloodMode::Both => cell.height == target.height && cell.prefab == target.prefab,
+            }
+        };
+
+        let mut visited = [false; MAP_SIZE];
+        let mut area = Vec::new();
+        let mut stack = vec![start];
+        visited[start.index()] = true;
+        while let Some(at) = stack.pop() {
+            area.push(at);
+            for neighbor in at.neighbors(Connectivity::Four) {
+                if !visited[neighbor.index()] && matches(self, neighbor) {
+                    visited[neighbor.index()] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        self.paint(area, paint)
+    }
+}
+
+fn in_circle(center: Coord, radius: usize, at: Coord) -> bool {
+    let dx = at.x().abs_diff(center.x());
+    let dy = at.y().abs_diff(center.y());
+    // anything past the grid diagonal covers the whole grid anyway, this keeps the squares from overflowing
+    let radius = radius.min(2 * GRID_SIZE);
+    // the extra `radius` rounds the circle so its edges don't end in single cells
+    dx * dx + dy * dy <= radius * radius + radius
+}
+
+fn line(from: Coord, to: Coord) -> Vec<Coord> {
+    let (mut x, mut y) = (from.x() as isize, from.y() as isize);
+    let (end_x, end_y) = (to.x() as isize, to.y() as isize);
+    let dx = (end_x - x).abs();
+    let dy = -(end_y - y).abs();
+    let step_x = if x < end_x { 1 } else { -1 };
+    let step_y = if y < end_y { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    let mut cells = Vec::with_capacity(GRID_SIZE * 2);
+    loop {
+        cells.push(Coord::new(x as usize, y as usize).unwrap());
+        if x == end_x && y == end_y {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+    cells
+}
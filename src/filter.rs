@@ -0,0 +1,107 @@
+use crate::{Connectivity, Coord, Height, MapPattern, Prefab, GRID_SIZE};
+
+/// Filters over the heights of a pattern, each reads the original heights and writes the result in one go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// average of the cells within `radius`
+    BoxBlur { radius: usize },
+    /// median of the cells within `radius`
+    Median { radius: usize },
+    /// snap heights to `levels` evenly spaced steps between the lowest and highest cell
+    /// Fewer than 2 levels flatten the pattern to its lowest cell.
+    Terrace { levels: usize },
+    /// lowest height within `radius`, shrinks plateaus and widens pits
+    Erode { radius: usize },
+    /// highest height within `radius`, grows plateaus and fills pits
+    Dilate { radius: usize },
+    /// flatten cells that stick out above (or below) all 4 neighbors by more than `threshold`
+    /// to the median of those neighbors
+    RemoveSpikes { threshold: i8 },
+}
+
+impl MapPattern {
+    /// run `filter` over the heights, returns the cells that changed
+    /// With `preserve_prefabs` cells carrying a prefab keep their height,
+    /// they still count as neighbors for the other cells.
+    pub fn apply_filter(&mut self, filter: Filter, preserve_prefabs: bool) -> Vec<Coord> {
+        let source = self.clone();
+        let mut changed = Vec::new();
+        for at in Coord::all() {
+            if preserve_prefabs && source.get_prefab(at) != Prefab::Empty {
+                continue;
+            }
+            let level = filter.apply(&source, at);
+            if level != source.get_level(at) {
+                self[at] = level;
+                changed.push(at);
+            }
+        }
+        changed
+    }
+}
+
+impl Filter {
+    fn apply(self, pattern: &MapPattern, at: Coord) -> Height {
+        let level = pattern.get_level(at);
+        match self {
+            Filter::BoxBlur { radius } => {
+                let window = window(pattern, at, radius);
+                let sum: i32 = window.iter().map(|h| i32::from(*h)).sum();
+                Height::clamped((sum as f32 / window.len() as f32).round() as i32)
+            }
+            Filter::Median { radius } => median(window(pattern, at, radius)),
+            Filter::Terrace { levels } => {
+                let low = i32::from(*pattern.level_map.iter().min().unwrap());
+                let high = i32::from(*pattern.level_map.iter().max().unwrap());
+                if levels < 2 || low == high {
+                    return Height::clamped(low);
+                }
+                let step = (high - low) as f32 / (levels - 1) as f32;
+                let band = ((i32::from(level) - low) as f32 / step).round();
+                Height::clamped(low + (band * step).round() as i32)
+            }
+            Filter::Erode { radius } => *window(pattern, at, radius).iter().min().unwrap(),
+            Filter::Dilate { radius } => *window(pattern, at, radius).iter().max().unwrap(),
+            Filter::RemoveSpikes { threshold } => {
+                let neighbors: Vec<Height> = pattern
+                    .neighbors(at, Connectivity::Four)
+                    .map(|cell| cell.height)
+                    .collect();
+                let difference = |h: &Height| i32::from(level) - i32::from(*h);
+                let threshold = i32::from(threshold);
+                let spike = neighbors.iter().all(|h| difference(h) > threshold);
+                let pit = neighbors.iter().all(|h| difference(h) < -threshold);
+                if spike || pit {
+                    median(neighbors)
+                } else {
+                    level
+                }
+            }
+        }
+    }
+}
+
+/// heights of all cells within `radius` of `at` (Chebyshev distance), including `at`
+fn window(pattern: &MapPattern, at: Coord, radius: usize) -> Vec<Height> {
+    let (left, right) = (
+        at.x().saturating_sub(radius),
+        at.x().saturating_add(radius).min(GRID_SIZE - 1),
+    );
+    let (top, bottom) = (
+        at.y().saturating_sub(radius),
+        at.y().saturating_add(radius).min(GRID_SIZE - 1),
+    );
+    let mut window = Vec::new();
+    for y in top..=bottom {
+        for x in left..=right {
+            window.push(pattern.get_level(Coord::new(x, y).unwrap()));
+        }
+    }
+    window
+}
+
+/// the lower median, so the result is always one of the given heights
+fn median(mut heights: Vec<Height>) -> Height {
+    heights.sort();
+    heights[(heights.len() - 1) / 2]
+}
@@ -0,0 +1,129 @@
+//! Reading and writing patterns in different text formats.
+//!
+//! Besides the native `.cgp` format, patterns can be stored as JSON, TOML or RON when the
+//! `json`, `toml` or `ron` features are enabled. These use the serde representation:
+//! `heights` and `prefabs` as 16x16 arrays indexed `[y][x]`, which is easy to diff and to read from other languages.
+
+use std::{fmt, fs, path::Path};
+
+use crate::{Error, MapPattern};
+
+/// A file format patterns can be stored in.
+/// Which variants exist depends on the enabled features, so matches outside this crate need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    Cgp,
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "toml")]
+    Toml,
+    #[cfg(feature = "ron")]
+    Ron,
+}
+
+impl Format {
+    /// every format compiled in
+    pub const ALL: &'static [Format] = &[
+        Format::Cgp,
+        #[cfg(feature = "json")]
+        Format::Json,
+        #[cfg(feature = "toml")]
+        Format::Toml,
+        #[cfg(feature = "ron")]
+        Format::Ron,
+    ];
+
+    /// file extension without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Cgp => "cgp",
+            #[cfg(feature = "json")]
+            Format::Json => "json",
+            #[cfg(feature = "toml")]
+            Format::Toml => "toml",
+            #[cfg(feature = "ron")]
+            Format::Ron => "ron",
+        }
+    }
+
+    /// the format for a file extension (with or without the dot), case insensitive
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        let extension = extension.trim_start_matches('.');
+        Format::ALL
+            .iter()
+            .copied()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+    }
+
+    /// the format for the extension of `path`
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        Format::from_extension(path.as_ref().extension()?.to_str()?)
+    }
+
+    pub fn encode(self, pattern: &MapPattern) -> Result<String, Error> {
+        match self {
+            Format::Cgp => Ok(pattern.to_cgp_string()),
+            #[cfg(feature = "json")]
+            Format::Json => serde_json::to_string_pretty(pattern).map_err(|e| self.error(e)),
+            #[cfg(feature = "toml")]
+            Format::Toml => toml::to_string(pattern).map_err(|e| self.error(e)),
+            #[cfg(feature = "ron")]
+            Format::Ron => ron::ser::to_string_pretty(
+                pattern,
+                ron::ser::PrettyConfig::default().depth_limit(2),
+            )
+            .map_err(|e| self.error(e)),
+        }
+    }
+
+    pub fn decode(self, input: &str) -> Result<MapPattern, Error> {
+        match self {
+            Format::Cgp => input.parse(),
+            #[cfg(feature = "json")]
+            Format::Json => serde_json::from_str(input).map_err(|e| self.error(e)),
+            #[cfg(feature = "toml")]
+            Format::Toml => toml::from_str(input).map_err(|e| self.error(e)),
+            #[cfg(feature = "ron")]
+            Format::Ron => ron::from_str(input).map_err(|e| self.error(e)),
+        }
+    }
+}
+
+#[cfg(any(feature = "json", feature = "toml", feature = "ron"))]
+impl Format {
+    fn error(self, e: impl fmt::Display) -> Error {
+        Error::UltraMapFormatError(self, e.to_string())
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Cgp => write!(f, "CGP"),
+            #[cfg(feature = "json")]
+            Format::Json => write!(f, "JSON"),
+            #[cfg(feature = "toml")]
+            Format::Toml => write!(f, "TOML"),
+            #[cfg(feature = "ron")]
+            Format::Ron => write!(f, "RON"),
+        }
+    }
+}
+
+fn detect(path: &Path) -> Result<Format, Error> {
+    Format::from_path(path).ok_or_else(|| Error::UltraMapUnknownFormat(path.display().to_string()))
+}
+
+/// load a pattern, the format is picked by the file extension
+pub fn load<P: AsRef<Path>>(path: P) -> Result<MapPattern, Error> {
+    let format = detect(path.as_ref())?;
+    format.decode(&fs::read_to_string(path)?)
+}
+
+/// save a pattern, the format is picked by the file extension
+pub fn save<P: AsRef<Path>>(pattern: &MapPattern, path: P) -> Result<(), Error> {
+    let format = detect(path.as_ref())?;
+    fs::write(path, format.encode(pattern)?)?;
+    Ok(())
+}
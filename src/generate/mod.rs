@@ -0,0 +1,273 @@
+//! Procedural pattern generators.
+//! Every generator is deterministic: the same seed and parameters always produce the same pattern,
+//! so generated maps can be shared by seed.
+
+mod noise;
+mod presets;
+mod rng;
+mod wfc;
+
+pub use noise::NoiseKind;
+pub use presets::{
+    CaveGenerator, MazeGenerator, PillarFieldGenerator, RingsGenerator, VoronoiGenerator,
+};
+pub use wfc::WfcGenerator;
+
+use crate::{Coord, Error, Height, MapPattern, Prefab, Symmetry};
+use noise::Noise;
+use rng::Rng;
+
+/// Common interface of all generators, so tools can list and configure them uniformly.
+pub trait PatternGenerator {
+    /// human readable name of the generator
+    fn name(&self) -> &'static str;
+
+    /// the tunable parameters with their current values
+    fn parameters(&self) -> Vec<Parameter>;
+
+    /// fails if there is no parameter called `name` or `value` is outside its range,
+    /// integer parameters are rounded
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error>;
+
+    fn generate(&self, seed: u64) -> Result<MapPattern, Error>;
+}
+
+/// A tunable parameter of a `PatternGenerator`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Parameter {
+    pub fn new(
+        name: &'static str,
+        description: &'static str,
+        value: f32,
+        min: f32,
+        max: f32,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            value,
+            min,
+            max,
+        }
+    }
+
+    /// checks that `name` is one of `parameters` and `value` lies within its range
+    fn check(parameters: &[Parameter], name: &str, value: f32) -> Result<f32, Error> {
+        let parameter = parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .ok_or_else(|| {
+                Error::UltraMapInvalidParameter(format!("unknown parameter `{}`", name))
+            })?;
+        if !(parameter.min..=parameter.max).contains(&value) {
+            return Err(Error::UltraMapInvalidParameter(format!(
+                "`{}` must be between {} and {}, got {}",
+                name, parameter.min, parameter.max, value
+            )));
+        }
+        Ok(value)
+    }
+}
+
+/// every generator that needs no training data, with default parameters
+pub fn presets() -> Vec<Box<dyn PatternGenerator>> {
+    vec![
+        Box::new(NoiseGenerator::default()),
+        Box::new(CaveGenerator::default()),
+        Box::new(MazeGenerator::default()),
+        Box::new(RingsGenerator::default()),
+        Box::new(VoronoiGenerator::default()),
+        Box::new(PillarFieldGenerator::default()),
+    ]
+}
+
+/// Prefabs the generators place, everything but `Prefab::Empty`.
+const PLACEABLE: [Prefab; 5] = [
+    Prefab::Melee,
+    Prefab::Projectile,
+    Prefab::JumpPad,
+    Prefab::Stairs,
+    Prefab::Hideous,
+];
+
+/// More octaves than this add no visible detail on a 16x16 grid.
+const MAX_OCTAVES: u32 = 8;
+
+/// Generates terrain from layered noise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseGenerator {
+    pub kind: NoiseKind,
+    /// the highest (and, negated, lowest) height the noise reaches
+    pub amplitude: f32,
+    /// noise features per cell, lower values give broader hills
+    pub frequency: f32,
+    /// number of noise layers, each adds finer detail, at most 8 are used
+    pub octaves: u32,
+    /// mirror the terrain and prefabs so the pattern has this symmetry
+    pub symmetry: Option<Symmetry>,
+    /// fraction of cells that get a random prefab, pits are skipped
+    pub prefab_density: f32,
+}
+
+impl Default for NoiseGenerator {
+    fn default() -> Self {
+        Self {
+            kind: NoiseKind::Perlin,
+            amplitude: 8.0,
+            frequency: 0.15,
+            octaves: 3,
+            symmetry: None,
+            prefab_density: 0.05,
+        }
+    }
+}
+
+impl PatternGenerator for NoiseGenerator {
+    fn name(&self) -> &'static str {
+        "noise"
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        let kind = match self.kind {
+            NoiseKind::Value => 0.0,
+            NoiseKind::Perlin => 1.0,
+            NoiseKind::Simplex => 2.0,
+        };
+        let symmetry = self.symmetry.map_or(0.0, |symmetry| {
+            (Symmetry::ALL.iter().position(|s| *s == symmetry).unwrap() + 1) as f32
+        });
+        vec![
+            Parameter::new("kind", "0 value, 1 Perlin, 2 simplex noise", kind, 0.0, 2.0),
+            Parameter::new(
+                "amplitude",
+                "highest height reached",
+                self.amplitude,
+                0.0,
+                50.0,
+            ),
+            Parameter::new(
+                "frequency",
+                "noise features per cell",
+                self.frequency,
+                0.01,
+                1.0,
+            ),
+            Parameter::new(
+                "octaves",
+                "layers of detail",
+                self.octaves as f32,
+                1.0,
+                MAX_OCTAVES as f32,
+            ),
+            Parameter::new(
+                "symmetry",
+                "0 none, 1 horizontal, 2 vertical, 3 both axes, 4 rotational, 5 diagonal",
+                symmetry,
+                0.0,
+                Symmetry::ALL.len() as f32,
+            ),
+            Parameter::new(
+                "prefab_density",
+                "fraction of cells with a prefab",
+                self.prefab_density,
+                0.0,
+                1.0,
+            ),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let value = Parameter::check(&self.parameters(), name, value)?;
+        match name {
+            "kind" => {
+                self.kind = match value.round() as u8 {
+                    0 => NoiseKind::Value,
+                    1 => NoiseKind::Perlin,
+                    _ => NoiseKind::Simplex,
+                }
+            }
+            "amplitude" => self.amplitude = value,
+            "frequency" => self.frequency = value,
+            "octaves" => self.octaves = value.round() as u32,
+            "symmetry" => {
+                self.symmetry = (value.round() as usize)
+                    .checked_sub(1)
+                    .map(|index| Symmetry::ALL[index])
+            }
+            _ => self.prefab_density = value,
+        }
+        Ok(())
+    }
+
+    fn generate(&self, seed: u64) -> Result<MapPattern, Error> {
+        let noise = Noise::new(self.kind, &mut Rng::fork(seed, 0));
+        let mut pattern = MapPattern::default();
+        for at in Coord::all() {
+            let (x, y) = (
+                at.x() as f32 * self.frequency,
+                at.y() as f32 * self.frequency,
+            );
+            let level = noise.fractal(x, y, self.octaves.clamp(1, MAX_OCTAVES)) * self.amplitude;
+            pattern[at] = Height::clamped(level.round() as i32);
+        }
+        let mut pattern = symmetrize(&pattern, self.symmetry);
+        scatter_prefabs(&mut pattern, self.prefab_density, self.symmetry, seed);
+        Ok(pattern)
+    }
+}
+
+/// every cell takes the contents of the first cell of its orbit under `symmetry`
+pub(crate) fn symmetrize(pattern: &MapPattern, symmetry: Option<Symmetry>) -> MapPattern {
+    let Some(symmetry) = symmetry else {
+        return pattern.clone();
+    };
+    let mut result = pattern.clone();
+    for at in Coord::all() {
+        let source = representative(symmetry, at);
+        result[at] = pattern.get_level(source);
+        result.prefab_map[at.index()] = pattern.get_prefab(source);
+    }
+    result
+}
+
+fn representative(symmetry: Symmetry, at: Coord) -> Coord {
+    symmetry
+        .orbit(at)
+        .into_iter()
+        .min_by_key(|at| at.index())
+        .unwrap()
+}
+
+/// place random prefabs on `density` of the cells that aren't pits, mirrored according to `symmetry`
+pub(crate) fn scatter_prefabs(
+    pattern: &mut MapPattern,
+    density: f32,
+    symmetry: Option<Symmetry>,
+    seed: u64,
+) {
+    let mut rng = Rng::fork(seed, 1);
+    for at in Coord::all() {
+        if let Some(symmetry) = symmetry {
+            if representative(symmetry, at) != at {
+                continue;
+            }
+        }
+        let place = rng.chance(density);
+        let prefab = PLACEABLE[rng.below(PLACEABLE.len())];
+        if !place || pattern.get_level(at) < Height::ZERO {
+            continue;
+        }
+        let orbit = symmetry.map_or_else(|| vec![at], |symmetry| symmetry.orbit(at));
+        for at in orbit {
+            pattern.prefab_map[at.index()] = prefab;
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use super::rng::Rng;
+
+/// Kind of gradient or value noise used by `NoiseGenerator`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NoiseKind {
+    Value,
+    #[default]
+    Perlin,
+    Simplex,
+}
+
+/// Seeded 2D noise, all kinds return values in roughly `-1.0..=1.0`.
+/// Only uses basic float arithmetic so results are identical on every platform.
+#[derive(Debug, Clone)]
+pub(crate) struct Noise {
+    kind: NoiseKind,
+    permutation: [u8; 512],
+}
+
+const GRADIENTS: [(f32, f32); 8] = [
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+    (0.707_106_77, 0.707_106_77),
+    (-0.707_106_77, 0.707_106_77),
+    (0.707_106_77, -0.707_106_77),
+    (-0.707_106_77, -0.707_106_77),
+];
+
+impl Noise {
+    pub(crate) fn new(kind: NoiseKind, rng: &mut Rng) -> Self {
+        let mut table: Vec<u8> = (0..=255).collect();
+        rng.shuffle(&mut table);
+        let mut permutation = [0; 512];
+        for (i, p) in permutation.iter_mut().enumerate() {
+            *p = table[i % 256];
+        }
+        Self { kind, permutation }
+    }
+
+    /// fractal sum of `octaves` layers, each with double the frequency and half the weight of the last
+    pub(crate) fn fractal(&self, x: f32, y: f32, octaves: u32) -> f32 {
+        let (mut sum, mut weight, mut total, mut frequency) = (0.0, 1.0, 0.0, 1.0);
+        for _ in 0..octaves.max(1) {
+            sum += self.sample(x * frequency, y * frequency) * weight;
+            total += weight;
+            weight *= 0.5;
+            frequency *= 2.0;
+        }
+        sum / total
+    }
+
+    pub(crate) fn sample(&self, x: f32, y: f32) -> f32 {
+        match self.kind {
+            NoiseKind::Value => self.value(x, y),
+            NoiseKind::Perlin => self.perlin(x, y),
+            NoiseKind::Simplex => self.simplex(x, y),
+        }
+    }
+
+    fn hash(&self, x: i32, y: i32) -> usize {
+        let x = (x & 255) as usize;
+        let y = (y & 255) as usize;
+        self.permutation[self.permutation[x] as usize + y] as usize
+    }
+
+    fn value(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (fade(x - x0), fade(y - y0));
+        let (ix, iy) = (x0 as i32, y0 as i32);
+        let corner = |dx, dy| self.hash(ix + dx, iy + dy) as f32 / 127.5 - 1.0;
+        lerp(
+            lerp(corner(0, 0), corner(1, 0), tx),
+            lerp(corner(0, 1), corner(1, 1), tx),
+            ty,
+        )
+    }
+
+    fn perlin(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (ix, iy) = (x0 as i32, y0 as i32);
+        let corner = |dx: i32, dy: i32| {
+            let (gx, gy) = GRADIENTS[self.hash(ix + dx, iy + dy) % 8];
+            gx * (fx - dx as f32) + gy * (fy - dy as f32)
+        };
+        let (tx, ty) = (fade(fx), fade(fy));
+        // gradients have length 1, so the raw range is about -0.71..=0.71
+        lerp(
+            lerp(corner(0, 0), corner(1, 0), tx),
+            lerp(corner(0, 1), corner(1, 1), tx),
+            ty,
+        ) * std::f32::consts::SQRT_2
+    }
+
+    fn simplex(&self, x: f32, y: f32) -> f32 {
+        const F2: f32 = 0.366_025_42; // (sqrt(3) - 1) / 2
+        const G2: f32 = 0.211_324_87; // (3 - sqrt(3)) / 6
+
+        let s = (x + y) * F2;
+        let (i, j) = ((x + s).floor(), (y + s).floor());
+        let t = (i + j) * G2;
+        let (x0, y0) = (x - (i - t), y - (j - t));
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let (x1, y1) = (x0 - i1 as f32 + G2, y0 - j1 as f32 + G2);
+        let (x2, y2) = (x0 - 1.0 + 2.0 * G2, y0 - 1.0 + 2.0 * G2);
+        let (i, j) = (i as i32, j as i32);
+
+        let corner = |dx: f32, dy: f32, ci: i32, cj: i32| {
+            let falloff = 0.5 - dx * dx - dy * dy;
+            if falloff < 0.0 {
+                return 0.0;
+            }
+            let (gx, gy) = GRADIENTS[self.hash(i + ci, j + cj) % 8];
+            let falloff = falloff * falloff;
+            falloff * falloff * (gx * dx + gy * dy)
+        };
+
+        70.0 * (corner(x0, y0, 0, 0) + corner(x1, y1, i1, j1) + corner(x2, y2, 1, 1))
+    }
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
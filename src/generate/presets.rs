@@ -0,0 +1,454 @@
+use super::{rng::Rng, Parameter, PatternGenerator};
+use crate::{Connectivity, Coord, Error, Height, MapPattern, GRID_SIZE, MAP_SIZE};
+
+/// Cave-like arenas: floor with blobs of pits, grown with a cellular automaton.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaveGenerator {
+    /// chance of a cell starting out as a pit
+    pub fill: f32,
+    /// smoothing steps of the automaton
+    pub iterations: u32,
+    pub pit_depth: i8,
+}
+
+impl Default for CaveGenerator {
+    fn default() -> Self {
+        Self {
+            fill: 0.45,
+            iterations: 4,
+            pit_depth: -10,
+        }
+    }
+}
+
+impl PatternGenerator for CaveGenerator {
+    fn name(&self) -> &'static str {
+        "cave"
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new(
+                "fill",
+                "chance of a cell starting as a pit",
+                self.fill,
+                0.0,
+                1.0,
+            ),
+            Parameter::new(
+                "iterations",
+                "smoothing steps",
+                self.iterations as f32,
+                0.0,
+                10.0,
+            ),
+            Parameter::new(
+                "pit_depth",
+                "height of the pits",
+                self.pit_depth as f32,
+                -50.0,
+                0.0,
+            ),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let value = Parameter::check(&self.parameters(), name, value)?;
+        match name {
+            "fill" => self.fill = value,
+            "iterations" => self.iterations = value.round() as u32,
+            _ => self.pit_depth = value.round() as i8,
+        }
+        Ok(())
+    }
+
+    fn generate(&self, seed: u64) -> Result<MapPattern, Error> {
+        let mut rng = Rng::new(seed);
+        let mut pits = [false; MAP_SIZE];
+        for pit in pits.iter_mut() {
+            *pit = rng.chance(self.fill);
+        }
+
+        for _ in 0..self.iterations {
+            let mut next = pits;
+            for at in Coord::all() {
+                // cells outside the grid count as floor, so the arena stays walkable along its edges
+                let neighbors = at
+                    .neighbors(Connectivity::Eight)
+                    .filter(|neighbor| pits[neighbor.index()])
+                    .count();
+                next[at.index()] = neighbors >= 5 || (pits[at.index()] && neighbors >= 4);
+            }
+            pits = next;
+        }
+
+        let depth = Height::new(self.pit_depth)?;
+        let mut pattern = MapPattern::default();
+        for at in Coord::all().filter(|at| pits[at.index()]) {
+            pattern[at] = depth;
+        }
+        Ok(pattern)
+    }
+}
+
+/// Maze of walls carved with a recursive backtracker.
+/// Corridors run along the even rows and columns, the last row and column are wall.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MazeGenerator {
+    pub wall_height: i8,
+    /// chance of knocking out an extra wall so the maze has loops
+    pub loops: f32,
+}
+
+impl Default for MazeGenerator {
+    fn default() -> Self {
+        Self {
+            wall_height: 6,
+            loops: 0.1,
+        }
+    }
+}
+
+impl PatternGenerator for MazeGenerator {
+    fn name(&self) -> &'static str {
+        "maze"
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new(
+                "wall_height",
+                "height of the walls",
+                self.wall_height as f32,
+                1.0,
+                50.0,
+            ),
+            Parameter::new(
+                "loops",
+                "chance of removing extra walls",
+                self.loops,
+                0.0,
+                1.0,
+            ),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let value = Parameter::check(&self.parameters(), name, value)?;
+        match name {
+            "wall_height" => self.wall_height = value.round() as i8,
+            _ => self.loops = value,
+        }
+        Ok(())
+    }
+
+    fn generate(&self, seed: u64) -> Result<MapPattern, Error> {
+        const CELLS: usize = GRID_SIZE / 2;
+        let mut rng = Rng::new(seed);
+        let wall = Height::new(self.wall_height)?;
+        let mut pattern = MapPattern::default();
+        for at in Coord::all() {
+            pattern[at] = wall;
+        }
+        let open = |pattern: &mut MapPattern, x: usize, y: usize| {
+            pattern[Coord::new(x, y).unwrap()] = Height::ZERO;
+        };
+
+        let mut visited = [[false; CELLS]; CELLS];
+        let mut stack = vec![(rng.below(CELLS), rng.below(CELLS))];
+        visited[stack[0].1][stack[0].0] = true;
+        open(&mut pattern, stack[0].0 * 2, stack[0].1 * 2);
+        while let Some(&(x, y)) = stack.last() {
+            let mut unvisited: Vec<(usize, usize)> = [(0, 1), (2, 1), (1, 0), (1, 2)]
+                .iter()
+                .filter_map(|&(dx, dy)| {
+                    let nx = (x + dx).checked_sub(1)?;
+                    let ny = (y + dy).checked_sub(1)?;
+                    (nx < CELLS && ny < CELLS && !visited[ny][nx]).then_some((nx, ny))
+                })
+                .collect();
+            if unvisited.is_empty() {
+                stack.pop();
+                continue;
+            }
+            rng.shuffle(&mut unvisited);
+            let (nx, ny) = unvisited[0];
+            visited[ny][nx] = true;
+            open(&mut pattern, x + nx, y + ny);
+            open(&mut pattern, nx * 2, ny * 2);
+            stack.push((nx, ny));
+        }
+
+        // walls between two corridor cells, either horizontally or vertically
+        for at in Coord::all() {
+            let (x, y) = (at.x(), at.y());
+            let between = (x % 2 == 1 && y % 2 == 0 && x < GRID_SIZE - 1)
+                || (x % 2 == 0 && y % 2 == 1 && y < GRID_SIZE - 1);
+            if between && pattern[at] == wall && rng.chance(self.loops) {
+                pattern[at] = Height::ZERO;
+            }
+        }
+        Ok(pattern)
+    }
+}
+
+/// Concentric rings around the center, each ring `step` higher than the one inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RingsGenerator {
+    pub ring_width: usize,
+    pub step: i8,
+    /// round rings instead of square ones
+    pub round: bool,
+}
+
+impl Default for RingsGenerator {
+    fn default() -> Self {
+        Self {
+            ring_width: 2,
+            step: 2,
+            round: true,
+        }
+    }
+}
+
+impl PatternGenerator for RingsGenerator {
+    fn name(&self) -> &'static str {
+        "rings"
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new(
+                "ring_width",
+                "width of each ring",
+                self.ring_width as f32,
+                1.0,
+                8.0,
+            ),
+            Parameter::new(
+                "step",
+                "height difference between rings",
+                self.step as f32,
+                -12.0,
+                12.0,
+            ),
+            Parameter::new(
+                "round",
+                "1 for round rings, 0 for square",
+                self.round as u8 as f32,
+                0.0,
+                1.0,
+            ),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let value = Parameter::check(&self.parameters(), name, value)?;
+        match name {
+            "ring_width" => self.ring_width = value.round() as usize,
+            "step" => self.step = value.round() as i8,
+            _ => self.round = value >= 0.5,
+        }
+        Ok(())
+    }
+
+    fn generate(&self, _seed: u64) -> Result<MapPattern, Error> {
+        let center = (GRID_SIZE as f32 - 1.0) / 2.0;
+        let mut pattern = MapPattern::default();
+        for at in Coord::all() {
+            let (dx, dy) = (
+                (at.x() as f32 - center).abs(),
+                (at.y() as f32 - center).abs(),
+            );
+            let distance = if self.round {
+                (dx * dx + dy * dy).sqrt()
+            } else {
+                dx.max(dy)
+            };
+            let ring = (distance.floor() as usize / self.ring_width.max(1)) as i32;
+            pattern[at] = Height::clamped(ring * self.step as i32);
+        }
+        Ok(pattern)
+    }
+}
+
+/// Flat terraces shaped like Voronoi cells around random sites.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoronoiGenerator {
+    pub sites: usize,
+    pub min_height: i8,
+    pub max_height: i8,
+    /// terrace heights are multiples of this
+    pub step: i8,
+}
+
+impl Default for VoronoiGenerator {
+    fn default() -> Self {
+        Self {
+            sites: 8,
+            min_height: 0,
+            max_height: 8,
+            step: 2,
+        }
+    }
+}
+
+impl PatternGenerator for VoronoiGenerator {
+    fn name(&self) -> &'static str {
+        "voronoi terraces"
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new("sites", "number of terraces", self.sites as f32, 1.0, 64.0),
+            Parameter::new(
+                "min_height",
+                "lowest terrace",
+                self.min_height as f32,
+                -50.0,
+                50.0,
+            ),
+            Parameter::new(
+                "max_height",
+                "highest terrace",
+                self.max_height as f32,
+                -50.0,
+                50.0,
+            ),
+            Parameter::new(
+                "step",
+                "terrace heights are multiples of this",
+                self.step as f32,
+                1.0,
+                50.0,
+            ),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let value = Parameter::check(&self.parameters(), name, value)?;
+        match name {
+            "sites" => self.sites = value.round() as usize,
+            "min_height" => self.min_height = value.round() as i8,
+            "max_height" => self.max_height = value.round() as i8,
+            _ => self.step = value.round() as i8,
+        }
+        Ok(())
+    }
+
+    fn generate(&self, seed: u64) -> Result<MapPattern, Error> {
+        let mut rng = Rng::new(seed);
+        let (low, high) = (
+            self.min_height.min(self.max_height) as i32,
+            self.min_height.max(self.max_height) as i32,
+        );
+        let step = self.step.max(1) as i32;
+        let sites: Vec<(Coord, Height)> = (0..self.sites.max(1))
+            .map(|_| {
+                let at = Coord::from_index(rng.below(MAP_SIZE)).unwrap();
+                let level = low + rng.below((high - low) as usize + 1) as i32;
+                (at, Height::clamped(level.div_euclid(step) * step))
+            })
+            .collect();
+
+        let mut pattern = MapPattern::default();
+        for at in Coord::all() {
+            let distance = |site: &Coord| {
+                let (dx, dy) = (site.x().abs_diff(at.x()), site.y().abs_diff(at.y()));
+                dx * dx + dy * dy
+            };
+            let (_, level) = sites.iter().min_by_key(|(site, _)| distance(site)).unwrap();
+            pattern[at] = *level;
+        }
+        Ok(pattern)
+    }
+}
+
+/// Flat floor with pillars on a regular grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PillarFieldGenerator {
+    /// distance between pillars
+    pub spacing: usize,
+    pub pillar_height: i8,
+    /// pillars are up to this much lower or higher than `pillar_height`
+    pub variation: i8,
+    /// chance of a pillar being left out
+    pub gaps: f32,
+}
+
+impl Default for PillarFieldGenerator {
+    fn default() -> Self {
+        Self {
+            spacing: 3,
+            pillar_height: 10,
+            variation: 3,
+            gaps: 0.2,
+        }
+    }
+}
+
+impl PatternGenerator for PillarFieldGenerator {
+    fn name(&self) -> &'static str {
+        "pillar field"
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new(
+                "spacing",
+                "distance between pillars",
+                self.spacing as f32,
+                2.0,
+                8.0,
+            ),
+            Parameter::new(
+                "pillar_height",
+                "height of the pillars",
+                self.pillar_height as f32,
+                -50.0,
+                50.0,
+            ),
+            Parameter::new(
+                "variation",
+                "random height variation",
+                self.variation as f32,
+                0.0,
+                20.0,
+            ),
+            Parameter::new("gaps", "chance of a missing pillar", self.gaps, 0.0, 1.0),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let value = Parameter::check(&self.parameters(), name, value)?;
+        match name {
+            "spacing" => self.spacing = value.round() as usize,
+            "pillar_height" => self.pillar_height = value.round() as i8,
+            "variation" => self.variation = value.round() as i8,
+            _ => self.gaps = value,
+        }
+        Ok(())
+    }
+
+    fn generate(&self, seed: u64) -> Result<MapPattern, Error> {
+        let mut rng = Rng::new(seed);
+        let spacing = self.spacing.max(1);
+        // center the grid of pillars so both borders get the same margin
+        let offset = (GRID_SIZE - 1) % spacing / 2;
+        let variation = self.variation.max(0) as i32;
+
+        let mut pattern = MapPattern::default();
+        for at in Coord::all() {
+            let on_grid = at.x() % spacing == offset && at.y() % spacing == offset;
+            if !on_grid {
+                continue;
+            }
+            let gap = rng.chance(self.gaps);
+            let jitter = rng.below(2 * variation as usize + 1) as i32 - variation;
+            if !gap {
+                pattern[at] = Height::clamped(self.pillar_height as i32 + jitter);
+            }
+        }
+        Ok(pattern)
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use super::{rng::Rng, Parameter, PatternGenerator};
+use crate::{Coord, Error, Height, MapPattern, Paint, Prefab, GRID_SIZE};
+
+type Value = (Height, Prefab);
+
+/// Wave Function Collapse (overlapping model) trained on example patterns.
+///
+/// Every `pattern_size` x `pattern_size` window of the examples becomes a tile, the output is built
+/// so that every window of it is one of those tiles and adjacent windows agree where they overlap.
+/// Heights and prefabs are learned together. To make the generator treat mirrored or rotated
+/// structures as equal, add transformed copies of the examples (see `MapPattern::transformed`).
+///
+/// Cells can be pinned before generating. The generator never backtracks: when it runs into
+/// a contradiction it restarts, and after `max_attempts` failed attempts it reports the contradiction.
+#[derive(Debug, Clone)]
+pub struct WfcGenerator {
+    pattern_size: usize,
+    tiles: Vec<Vec<Value>>,
+    weights: Vec<u32>,
+    /// `compatible[offset][u]`: tiles that can sit at a position when `u` sits at `position + offset`
+    compatible: Vec<Vec<Bits>>,
+    offsets: Vec<(isize, isize)>,
+    pins: Vec<(Coord, Paint)>,
+    pub max_attempts: u32,
+}
+
+impl WfcGenerator {
+    /// learn the tiles of `examples`, `pattern_size` must be between 1 and 16
+    pub fn new(examples: &[MapPattern], pattern_size: usize) -> Result<Self, Error> {
+        if examples.is_empty() {
+            return Err(Error::UltraMapInvalidParameter(
+                "at least one example is needed".to_string(),
+            ));
+        }
+        if !(1..=GRID_SIZE).contains(&pattern_size) {
+            return Err(Error::UltraMapInvalidParameter(format!(
+                "pattern size must be between 1 and {}, got {}",
+                GRID_SIZE, pattern_size
+            )));
+        }
+
+        let mut index: HashMap<Vec<Value>, usize> = HashMap::new();
+        let mut tiles = Vec::new();
+        let mut weights = Vec::new();
+        for example in examples {
+            for y in 0..=GRID_SIZE - pattern_size {
+                for x in 0..=GRID_SIZE - pattern_size {
+                    let tile = window(example, x, y, pattern_size);
+                    let id = *index.entry(tile.clone()).or_insert_with(|| {
+                        tiles.push(tile);
+                        weights.push(0);
+                        tiles.len() - 1
+                    });
+                    weights[id] += 1;
+                }
+            }
+        }
+
+        let n = pattern_size as isize;
+        let offsets: Vec<(isize, isize)> = (1 - n..n)
+            .flat_map(|dy| (1 - n..n).map(move |dx| (dx, dy)))
+            .filter(|offset| *offset != (0, 0))
+            .collect();
+        let compatible = offsets
+            .iter()
+            .map(|&(dx, dy)| {
+                (0..tiles.len())
+                    .map(|u| {
+                        let mut bits = Bits::empty(tiles.len());
+                        for t in 0..tiles.len() {
+                            if agrees(&tiles[t], &tiles[u], dx, dy, pattern_size) {
+                                bits.insert(t);
+                            }
+                        }
+                        bits
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            pattern_size,
+            tiles,
+            weights,
+            compatible,
+            offsets,
+            pins: Vec::new(),
+            max_attempts: 10,
+        })
+    }
+
+    /// number of distinct tiles learned from the examples
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// force the cell at `at` to the parts of `paint` that are set
+    pub fn pin(&mut self, at: Coord, paint: Paint) {
+        self.pins.retain(|(pinned, _)| *pinned != at);
+        self.pins.push((at, paint));
+    }
+
+    pub fn clear_pins(&mut self) {
+        self.pins.clear();
+    }
+
+    /// positions per axis a tile can be placed at
+    fn side(&self) -> usize {
+        GRID_SIZE - self.pattern_size + 1
+    }
+
+    /// runs one attempt, a contradiction is reported as the top left cell of the failing window
+    fn attempt(&self, rng: &mut Rng) -> Result<MapPattern, Coord> {
+        let side = self.side();
+        let mut wave = vec![Bits::full(self.tiles.len()); side * side];
+
+        for (at, paint) in &self.pins {
+            for py in at.y().saturating_sub(self.pattern_size - 1)..=at.y().min(side - 1) {
+                for px in at.x().saturating_sub(self.pattern_size - 1)..=at.x().min(side - 1) {
+                    let offset = (at.y() - py) * self.pattern_size + (at.x() - px);
+                    let allowed = &mut wave[py * side + px];
+                    for t in 0..self.tiles.len() {
+                        let (height, prefab) = self.tiles[t][offset];
+                        if paint.height.is_some_and(|h| h != height)
+                            || paint.prefab.is_some_and(|p| p != prefab)
+                        {
+                            allowed.remove(t);
+                        }
+                    }
+                }
+            }
+        }
+        self.propagate(&mut wave, (0..side * side).collect())?;
+
+        loop {
+            // collapse the undecided position with the fewest options left, ties are broken randomly
+            let mut best: Option<(usize, usize)> = None;
+            let mut ties = 0;
+            for (position, allowed) in wave.iter().enumerate() {
+                let count = allowed.count();
+                if count <= 1 {
+                    continue;
+                }
+                match best {
+                    Some((_, fewest)) if count > fewest => {}
+                    Some((_, fewest)) if count == fewest => {
+                        ties += 1;
+                        if rng.below(ties + 1) == 0 {
+                            best = Some((position, count));
+                        }
+                    }
+                    _ => {
+                        best = Some((position, count));
+                        ties = 0;
+                    }
+                }
+            }
+            let Some((position, _)) = best else {
+                break;
+            };
+
+            let options: Vec<usize> = wave[position].iter().collect();
+            let total: u64 = options.iter().map(|t| self.weights[*t] as u64).sum();
+            let mut pick = rng.next_u64() % total;
+            let mut chosen = options[0];
+            for t in options {
+                let weight = self.weights[t] as u64;
+                if pick < weight {
+                    chosen = t;
+                    break;
+                }
+                pick -= weight;
+            }
+
+            let mut collapsed = Bits::empty(self.tiles.len());
+            collapsed.insert(chosen);
+            wave[position] = collapsed;
+            self.propagate(&mut wave, vec![position])?;
+        }
+
+        let mut pattern = MapPattern::default();
+        for at in Coord::all() {
+            let (px, py) = (at.x().min(side - 1), at.y().min(side - 1));
+            let tile = wave[py * side + px].iter().next().unwrap();
+            let (height, prefab) =
+                self.tiles[tile][(at.y() - py) * self.pattern_size + (at.x() - px)];
+            pattern[at] = height;
+            pattern.prefab_map[at.index()] = prefab;
+        }
+        Ok(pattern)
+    }
+
+    /// removes tiles that no longer fit next to the tiles left at the changed positions
+    fn propagate(&self, wave: &mut [Bits], mut changed: Vec<usize>) -> Result<(), Coord> {
+        let side = self.side() as isize;
+        let position_coord = |position: usize| {
+            Coord::new(position % side as usize, position / side as usize).unwrap()
+        };
+
+        while let Some(position) = changed.pop() {
+            if wave[position].count() == 0 {
+                return Err(position_coord(position));
+            }
+            let (px, py) = (position as isize % side, position as isize / side);
+            for (d, &(dx, dy)) in self.offsets.iter().enumerate() {
+                let (qx, qy) = (px + dx, py + dy);
+                if !(0..side).contains(&qx) || !(0..side).contains(&qy) {
+                    continue;
+                }
+                let neighbor = (qy * side + qx) as usize;
+                let mut reduced = false;
+                let options: Vec<usize> = wave[neighbor].iter().collect();
+                for u in options {
+                    if !self.compatible[d][u].intersects(&wave[position]) {
+                        wave[neighbor].remove(u);
+                        reduced = true;
+                    }
+                }
+                if reduced {
+                    if wave[neighbor].count() == 0 {
+                        return Err(position_coord(neighbor));
+                    }
+                    changed.push(neighbor);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn window(pattern: &MapPattern, x: usize, y: usize, size: usize) -> Vec<Value> {
+    let mut tile = Vec::with_capacity(size * size);
+    for dy in 0..size {
+        for dx in 0..size {
+            let at = Coord::new(x + dx, y + dy).unwrap();
+            tile.push((pattern.get_level(at), pattern.get_prefab(at)));
+        }
+    }
+    tile
+}
+
+/// whether tile `u` placed at `(dx, dy)` relative to tile `t` matches it on their overlap
+fn agrees(t: &[Value], u: &[Value], dx: isize, dy: isize, size: usize) -> bool {
+    let size = size as isize;
+    for y in dy.max(0)..(size + dy).min(size) {
+        for x in dx.max(0)..(size + dx).min(size) {
+            let a = t[(y * size + x) as usize];
+            let b = u[((y - dy) * size + (x - dx)) as usize];
+            if a != b {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Fixed size bit set over tile ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bits {
+    words: Vec<u64>,
+}
+
+impl Bits {
+    fn empty(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn full(len: usize) -> Self {
+        let mut bits = Self::empty(len);
+        for i in 0..len {
+            bits.insert(i);
+        }
+        bits
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    fn remove(&mut self, i: usize) {
+        self.words[i / 64] &= !(1 << (i % 64));
+    }
+
+    fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn intersects(&self, other: &Bits) -> bool {
+        self.words.iter().zip(&other.words).any(|(a, b)| a & b != 0)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| i * 64 + bit)
+        })
+    }
+}
+
+impl PatternGenerator for WfcGenerator {
+    fn name(&self) -> &'static str {
+        "wave function collapse"
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![Parameter::new(
+            "max_attempts",
+            "restarts before giving up on a contradiction",
+            self.max_attempts as f32,
+            1.0,
+            1000.0,
+        )]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), Error> {
+        let value = Parameter::check(&self.parameters(), name, value)?;
+        self.max_attempts = value.round() as u32;
+        Ok(())
+    }
+
+    fn generate(&self, seed: u64) -> Result<MapPattern, Error> {
+        let mut rng = Rng::new(seed);
+        let mut contradiction = None;
+        for _ in 0..self.max_attempts.max(1) {
+            match self.attempt(&mut rng) {
+                Ok(pattern) => return Ok(pattern),
+                Err(at) => contradiction = Some(at),
+            }
+        }
+        Err(Error::UltraMapContradiction(contradiction.unwrap()))
+    }
+}
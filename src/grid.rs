@@ -0,0 +1,123 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{Coord, Height, MapPattern, Prefab, GRID_SIZE};
+
+/// Everything stored for a single cell of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub coord: Coord,
+    pub height: Height,
+    pub prefab: Prefab,
+}
+
+/// Which cells count as neighbors of a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// the cells sharing an edge
+    Four,
+    /// the cells sharing an edge or a corner
+    Eight,
+}
+
+const OFFSETS: [(isize, isize); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+impl Coord {
+    /// every coordinate of the grid, in the order cells are stored
+    pub fn all() -> impl Iterator<Item = Coord> {
+        (0..GRID_SIZE * GRID_SIZE).map(|index| Coord::from_index(index).unwrap())
+    }
+
+    /// neighboring coordinates that lie inside the grid
+    pub fn neighbors(self, connectivity: Connectivity) -> impl Iterator<Item = Coord> {
+        let count = match connectivity {
+            Connectivity::Four => 4,
+            Connectivity::Eight => 8,
+        };
+        OFFSETS[..count].iter().filter_map(move |&(dx, dy)| {
+            let x = self.x().checked_add_signed(dx)?;
+            let y = self.y().checked_add_signed(dy)?;
+            Coord::new(x, y).ok()
+        })
+    }
+}
+
+impl MapPattern {
+    pub fn cell(&self, at: Coord) -> Cell {
+        Cell {
+            coord: at,
+            height: self.level_map[at.index()],
+            prefab: self.prefab_map[at.index()],
+        }
+    }
+
+    /// every cell of the pattern, in the order cells are stored
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        Coord::all().map(move |at| self.cell(at))
+    }
+
+    /// the 16 rows of the pattern, as they appear in the `.cgp` file
+    pub fn rows(&self) -> impl Iterator<Item = [Cell; GRID_SIZE]> + '_ {
+        (0..GRID_SIZE).map(move |row| {
+            std::array::from_fn(|column| self.cell_at_index(row * GRID_SIZE + column))
+        })
+    }
+
+    /// the 16 columns of the pattern, each from top to bottom
+    pub fn columns(&self) -> impl Iterator<Item = [Cell; GRID_SIZE]> + '_ {
+        (0..GRID_SIZE).map(move |column| {
+            std::array::from_fn(|row| self.cell_at_index(row * GRID_SIZE + column))
+        })
+    }
+
+    /// cells next to `at`, cells outside the grid are skipped
+    pub fn neighbors(
+        &self,
+        at: Coord,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = Cell> + '_ {
+        at.neighbors(connectivity).map(move |at| self.cell(at))
+    }
+
+    fn cell_at_index(&self, index: usize) -> Cell {
+        self.cell(Coord::from_index(index).unwrap())
+    }
+}
+
+impl Index<Coord> for MapPattern {
+    type Output = Height;
+
+    fn index(&self, at: Coord) -> &Self::Output {
+        &self.level_map[at.index()]
+    }
+}
+
+impl IndexMut<Coord> for MapPattern {
+    fn index_mut(&mut self, at: Coord) -> &mut Self::Output {
+        &mut self.level_map[at.index()]
+    }
+}
+
+/// Panics if the coordinates are outside the grid.
+impl Index<(usize, usize)> for MapPattern {
+    type Output = Height;
+
+    fn index(&self, at: (usize, usize)) -> &Self::Output {
+        &self[Coord::try_from(at).expect("coordinates outside the grid")]
+    }
+}
+
+/// Panics if the coordinates are outside the grid.
+impl IndexMut<(usize, usize)> for MapPattern {
+    fn index_mut(&mut self, at: (usize, usize)) -> &mut Self::Output {
+        &mut self[Coord::try_from(at).expect("coordinates outside the grid")]
+    }
+}
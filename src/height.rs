@@ -0,0 +1,98 @@
+use std::{fmt, ops::Neg};
+
+use crate::Error;
+
+/// Height level of a single cell, always within -50..=50 (0 is base height).
+/// With the `serde` feature it is stored as a plain number, out of range numbers fail to deserialize.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "i8", into = "i8")
+)]
+pub struct Height(i8);
+
+impl Height {
+    pub const MIN: Height = Height(-50);
+    pub const MAX: Height = Height(50);
+    pub const ZERO: Height = Height(0);
+
+    /// Fails if `level` is not within -50..=50.
+    pub fn new(level: i8) -> Result<Self, Error> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&level) {
+            Ok(Self(level))
+        } else {
+            Err(Error::UltraMapHeightOutOfRange)
+        }
+    }
+
+    /// clamps `level` into -50..=50
+    pub fn clamped(level: i32) -> Self {
+        Self(level.clamp(Self::MIN.0 as i32, Self::MAX.0 as i32) as i8)
+    }
+
+    pub fn get(self) -> i8 {
+        self.0
+    }
+
+    /// `None` if the result would leave -50..=50
+    pub fn checked_add(self, rhs: i8) -> Option<Self> {
+        Self::new(self.0.checked_add(rhs)?).ok()
+    }
+
+    /// `None` if the result would leave -50..=50
+    pub fn checked_sub(self, rhs: i8) -> Option<Self> {
+        Self::new(self.0.checked_sub(rhs)?).ok()
+    }
+
+    pub fn saturating_add(self, rhs: i8) -> Self {
+        Self::clamped(self.0 as i32 + rhs as i32)
+    }
+
+    pub fn saturating_sub(self, rhs: i8) -> Self {
+        Self::clamped(self.0 as i32 - rhs as i32)
+    }
+}
+
+impl Neg for Height {
+    type Output = Height;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl TryFrom<i8> for Height {
+    type Error = Error;
+
+    fn try_from(level: i8) -> Result<Self, Self::Error> {
+        Height::new(level)
+    }
+}
+
+impl TryFrom<i32> for Height {
+    type Error = Error;
+
+    fn try_from(level: i32) -> Result<Self, Self::Error> {
+        let level = i8::try_from(level).map_err(|_| Error::UltraMapHeightOutOfRange)?;
+        Height::new(level)
+    }
+}
+
+impl From<Height> for i8 {
+    fn from(height: Height) -> Self {
+        height.0
+    }
+}
+
+impl From<Height> for i32 {
+    fn from(height: Height) -> Self {
+        height.0 as i32
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::BTreeMap;
+
+use crate::{Cell, Coord, Error, Height, MapPattern, Prefab};
+
+/// A single cell edit, stores the cell before and after so it can be reverted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    pub before: Cell,
+    pub after: Cell,
+}
+
+impl Change {
+    fn write(pattern: &mut MapPattern, cell: Cell) {
+        pattern.level_map[cell.coord.index()] = cell.height;
+        pattern.prefab_map[cell.coord.index()] = cell.prefab;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// child followed by `redo`, the most recently visited branch
+    redo_child: Option<usize>,
+    changes: Vec<Change>,
+}
+
+impl Node {
+    fn new(parent: Option<usize>, changes: Vec<Change>) -> Self {
+        Self {
+            parent,
+            children: Vec::new(),
+            redo_child: None,
+            changes,
+        }
+    }
+}
+
+/// Undo/redo history for edits made to a `MapPattern`.
+///
+/// Every edit is stored as the list of cells it changed instead of a copy of the whole pattern.
+/// Edits between `begin_group` and `end_group` are undone and redone as one step.
+/// Making an edit after undoing doesn't discard the undone steps, they stay reachable as a redo branch.
+/// At most `capacity` steps are kept, when that is exceeded abandoned redo branches are dropped first
+/// and then the oldest steps. The most recent step can always be undone as long as `capacity` is at least 1.
+///
+/// The history doesn't own the pattern, it has to be passed the same pattern on every call.
+#[derive(Debug, Clone)]
+pub struct EditHistory {
+    nodes: BTreeMap<usize, Node>,
+    root: usize,
+    current: usize,
+    next_id: usize,
+    capacity: usize,
+    group_depth: usize,
+    pending: Vec<Change>,
+}
+
+impl EditHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            nodes: BTreeMap::from([(0, Node::new(None, Vec::new()))]),
+            root: 0,
+            current: 0,
+            next_id: 1,
+            capacity,
+            group_depth: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// set the height of a cell and record the change
+    pub fn set_level<C, H>(
+        &mut self,
+        pattern: &mut MapPattern,
+        at: C,
+        level: H,
+    ) -> Result<(), Error>
+    where
+        C: TryInto<Coord>,
+        H: TryInto<Height>,
+        Error: From<C::Error> + From<H::Error>,
+    {
+        let at = at.try_into()?;
+        let level = level.try_into()?;
+        let before = pattern.cell(at);
+        pattern.level_map[at.index()] = level;
+        self.push(before, pattern.cell(at));
+        Ok(())
+    }
+
+    /// set the prefab of a cell and record the change
+    pub fn set_prefab<C>(
+        &mut self,
+        pattern: &mut MapPattern,
+        at: C,
+        prefab: Prefab,
+    ) -> Result<(), Error>
+    where
+        C: TryInto<Coord>,
+        Error: From<C::Error>,
+    {
+        let at = at.try_into()?;
+        let before = pattern.cell(at);
+        pattern.prefab_map[at.index()] = prefab;
+        self.push(before, pattern.cell(at));
+        Ok(())
+    }
+
+    /// run an arbitrary edit and record every cell it changed as one step
+    /// (or as part of the open group)
+    pub fn record<T>(
+        &mut self,
+        pattern: &mut MapPattern,
+        edit: impl FnOnce(&mut MapPattern) -> T,
+    ) -> T {
+        let before = pattern.clone();
+        let result = edit(pattern);
+        let changes = Coord::all()
+            .map(|at| Change {
+                before: before.cell(at),
+                after: pattern.cell(at),
+            })
+            .filter(|change| change.before != change.after);
+        self.pending.extend(changes);
+        if self.group_depth == 0 {
+            self.commit();
+        }
+        result
+    }
+
+    /// start grouping edits into one step, groups can be nested
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    /// finish the group started by the matching `begin_group`
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 {
+            self.commit();
+        }
+    }
+
+    /// revert the last step, returns false if there is nothing to undo
+    /// Closes any open group first.
+    pub fn undo(&mut self, pattern: &mut MapPattern) -> bool {
+        self.close_groups();
+        let node = &self.nodes[&self.current];
+        let Some(parent) = node.parent else {
+            return false;
+        };
+        for change in node.changes.iter().rev() {
+            Change::write(pattern, change.before);
+        }
+        self.nodes.get_mut(&parent).unwrap().redo_child = Some(self.current);
+        self.current = parent;
+        true
+    }
+
+    /// redo the most recently undone step, returns false if there is nothing to redo
+    pub fn redo(&mut self, pattern: &mut MapPattern) -> bool {
+        self.close_groups();
+        match self.nodes[&self.current].redo_child {
+            Some(child) => self.redo_node(pattern, child),
+            None => false,
+        }
+    }
+
+    /// number of steps that can be redone from here, one per branch
+    pub fn redo_branches(&self) -> usize {
+        self.nodes[&self.current].children.len()
+    }
+
+    /// redo the step of the given branch, branches are numbered from oldest to newest
+    /// Returns false if there is no such branch.
+    pub fn redo_branch(&mut self, pattern: &mut MapPattern, branch: usize) -> bool {
+        self.close_groups();
+        match self.nodes[&self.current].children.get(branch) {
+            Some(&child) => self.redo_node(pattern, child),
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.current != self.root || !self.pending.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.redo_branches() > 0
+    }
+
+    /// number of steps stored, across all branches
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn redo_node(&mut self, pattern: &mut MapPattern, child: usize) -> bool {
+        for change in &self.nodes[&child].changes {
+            Change::write(pattern, change.after);
+        }
+        self.nodes.get_mut(&self.current).unwrap().redo_child = Some(child);
+        self.current = child;
+        true
+    }
+
+    /// records a single cell edit without snapshotting the pattern
+    fn push(&mut self, before: Cell, after: Cell) {
+        if before != after {
+            self.pending.push(Change { before, after });
+        }
+        if self.group_depth == 0 {
+            self.commit();
+        }
+    }
+
+    fn close_groups(&mut self) {
+        if self.group_depth > 0 {
+            self.group_depth = 0;
+            self.commit();
+        }
+    }
+
+    fn commit(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let changes = std::mem::take(&mut self.pending);
+        self.nodes
+            .insert(id, Node::new(Some(self.current), changes));
+
+        let parent = self.nodes.get_mut(&self.current).unwrap();
+        parent.children.push(id);
+        parent.redo_child = Some(id);
+        self.current = id;
+
+        self.prune();
+    }
+
+    /// drops steps until the history fits its capacity
+    /// Abandoned redo branches go first, oldest first, then the oldest steps of the current path.
+    /// The current step itself is never dropped.
+    fn prune(&mut self) {
+        while self.len() > self.capacity {
+            if let Some(leaf) = self.oldest_dead_leaf() {
+                self.remove_leaf(leaf);
+                continue;
+            }
+            if self.root == self.current {
+                break;
+            }
+
+            // without dead branches the tree is a single path from the root to `current`
+            let root = self.nodes.remove(&self.root).unwrap();
+            let keep = root.children[0];
+            let new_root = self.nodes.get_mut(&keep).unwrap();
+            new_root.parent = None;
+            new_root.changes.clear();
+            self.root = keep;
+        }
+    }
+
+    /// the oldest step without children that isn't `current` or one of its ancestors
+    fn oldest_dead_leaf(&self) -> Option<usize> {
+        let mut path = Vec::new();
+        let mut node = Some(self.current);
+        while let Some(id) = node {
+            path.push(id);
+            node = self.nodes[&id].parent;
+        }
+        self.nodes
+            .iter()
+            .find(|(id, node)| node.children.is_empty() && !path.contains(id))
+            .map(|(id, _)| *id)
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        let node = self.nodes.remove(&leaf).unwrap();
+        if let Some(parent) = node.parent.and_then(|p| self.nodes.get_mut(&p)) {
+            parent.children.retain(|c| *c != leaf);
+            if parent.redo_child == Some(leaf) {
+                parent.redo_child = parent.children.last().copied();
+            }
+        }
+    }
+}
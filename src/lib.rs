@@ -1,252 +1,339 @@
-use std::{
-    fs::File,
-    io::{Read, Write},
-    num::TryFromIntError,
-};
-
-use thiserror::Error;
-
-const MAP_SIZE: usize = 256;
-
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error("Error occurred while converting map")]
-    UltraMapConversionError,
-
-    #[error("Error occurred while reading map")]
-    UltraMapParsingError(#[from] TryFromIntError),
-
-    #[error("Error occurred while writing to file")]
-    UltraMapIoError(#[from] std::io::Error),
-
-    #[error("Invalid coordinates")]
-    UltraMapIndexOutOfBounds,
-
-    #[error("Invalid character")]
-    UltraMapInvalidCharacter,
-}
-
-/// Each map is 16x16, each cell can range from -50 to 50 (0 is base height).
-/// The level_map describes the height level while prefab_map indicates if and what prefabs should be placed on the cell.
-#[derive(Debug, Clone)]
-pub struct MapPattern {
-    level_map: [i8; MAP_SIZE],
-    prefab_map: [Prefab; MAP_SIZE],
-}
-
-impl Default for MapPattern {
-    fn default() -> Self {
-        Self {
-            level_map: [0; MAP_SIZE],
-            prefab_map: [Prefab::Empty; MAP_SIZE],
-        }
-    }
-}
-
-impl MapPattern {
-    pub fn from(path: &str) -> Result<Self, Error> {
-        let mut file = File::open(path)?;
-        let mut input = String::new();
-
-        file.read_to_string(&mut input)?;
-
-        let mut level_map = [0; 256];
-        let mut prefab_map = ['0'; 256];
-
-        let mut in_parentheses = false;
-
-        let mut temp = String::new();
-        let mut index = 0;
-
-        for c in input.chars() {
-            if c.is_whitespace() {
-                continue;
-            }
-
-            if c == '(' {
-                in_parentheses = true;
-                continue;
-            } else if c == ')' {
-                in_parentheses = false;
-                level_map[index] = temp.parse().unwrap();
-                temp.clear();
-                index += 1;
-                continue;
-            }
-
-            if in_parentheses {
-                temp.push(c);
-                continue;
-            }
-
-            if index < 256 {
-                let digit = char::to_digit(c, 10).ok_or(Error::UltraMapConversionError)?;
-                let digit: i8 = i8::try_from(digit)?;
-                level_map[index] = digit;
-            } else {
-                prefab_map[index - 256] = c;
-            }
-            index += 1;
-        }
-
-        let mut prefab_map_arr = [Prefab::Empty; 256];
-        for (index, c) in prefab_map.iter().enumerate() {
-            if *c != '0' {
-                prefab_map_arr[index] = Prefab::try_from(*c).unwrap();
-            }
-        }
-
-        Ok(Self {
-            level_map,
-            prefab_map: prefab_map_arr,
-        })
-    }
-    pub fn get_level_map(&self) -> &[i8] {
-        self.level_map.as_slice()
-    }
-
-    pub fn get_level_map_mut(&mut self) -> &mut [i8] {
-        self.level_map.as_mut_slice()
-    }
-
-    pub fn get_prefab_map(&self) -> &[Prefab] {
-        self.prefab_map.as_slice()
-    }
-    pub fn get_prefab_map_mut(&mut self) -> &mut [Prefab] {
-        self.prefab_map.as_mut_slice()
-    }
-
-    pub fn get_map_raw(&self) -> String {
-        let mut returnee = String::new();
-        for i in self.level_map.iter() {
-            let c = char::from_digit(*i as u32, 10).unwrap();
-            returnee.push(c);
-        }
-
-        returnee.push('\n');
-
-        for c in self.prefab_map {
-            returnee.push(c.into());
-        }
-
-        returnee
-    }
-
-    /// set height level of tile
-    /// Note! level cannot be higher than 50 or lower than -50
-    /// Coordinates are 0 indexed
-    pub fn set_level_at(&mut self, x: usize, y: usize, level: i8) {
-        let index = x * 16 + y;
-        if index >= 256 {
-            panic!("Invalid");
-        }
-        if !(-50..=50).contains(&level) {
-            panic!("Level cannot be greater than 50 or lower than -50")
-        }
-        self.level_map[index] = level;
-    }
-
-    pub fn set_level_at_index(&mut self, index: usize, level: i8) {
-        if index >= 256 {
-            panic!("Index out of bounds")
-        }
-        if level > 50 {
-            panic!("Level cannot be higher than 50 or lower -50")
-        }
-
-        self.level_map[index] = level;
-    }
-
-    /// set prefab at given tile
-    /// x and y coordinates are 0 indexed
-    pub fn set_prefab_at(&mut self, x: usize, y: usize, prefab: Prefab) {
-        self.prefab_map[x * 16 + y] = prefab;
-    }
-
-    pub fn save_pattern(&self, name: &str) -> Result<(), Error> {
-        let mut save = String::new();
-        let mut f = File::create(format!("{}.cgp", name))?;
-
-        for (index, i) in self.level_map.iter().enumerate() {
-            let c = if i.to_string().len() > 1 {
-                format!("({})", i)
-            } else {
-                i.to_string()
-            };
-            if index % 16 == 0 && index > 0 {
-                save.push('\n');
-            }
-            save.push_str(&c);
-        }
-
-        save.push('\n');
-        save.push('\n');
-
-        for (index, c) in self.prefab_map.as_slice().iter().enumerate() {
-            if index % 16 == 0 && index > 0 {
-                save.push('\n');
-            }
-            save.push(Prefab::match_char(c));
-        }
-
-        writeln!(f, "{}", save)?;
-        Ok(())
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-pub enum Prefab {
-    Empty,
-    Melee,
-    Projectile,
-    JumpPad,
-    Stairs,
-    Hideous,
-}
-
-impl Prefab {
-    pub fn match_char(prefab: &Prefab) -> char {
-        match prefab {
-            Prefab::Empty => '0',
-            Prefab::Melee => 'n',
-            Prefab::Projectile => 'p',
-            Prefab::JumpPad => 'J',
-            Prefab::Stairs => 's',
-            Prefab::Hideous => 'H',
-        }
-    }
-}
-
-impl Into<char> for Prefab {
-    fn into(self) -> char {
-        match self {
-            Prefab::Empty => '0',
-            Prefab::Melee => 'n',
-            Prefab::Projectile => 'p',
-            Prefab::JumpPad => 'J',
-            Prefab::Stairs => 's',
-            Prefab::Hideous => 'H',
-        }
-    }
-}
-
-impl TryFrom<char> for Prefab {
-    type Error = Error;
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            'n' => Ok(Prefab::Melee),
-            'p' => Ok(Prefab::Projectile),
-            'J' => Ok(Prefab::JumpPad),
-            'H' => Ok(Prefab::Hideous),
-            's' => Ok(Prefab::Stairs),
-            '0' => Ok(Prefab::Empty),
-            _ => Err(Error::UltraMapInvalidCharacter),
-        }
-    }
-}
-
-impl Default for Prefab {
-    fn default() -> Self {
-        Prefab::Projectile
-    }
-}
+use std::{
+    convert::Infallible,
+    fmt,
+    fs::File,
+    io::{Read, Write},
+    num::TryFromIntError,
+    path::Path,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+mod arith;
+mod cgp;
+mod coord;
+mod diff;
+mod draw;
+mod filter;
+pub mod format;
+pub mod generate;
+mod grid;
+mod height;
+mod history;
+mod merge;
+mod reach;
+mod region;
+#[cfg(feature = "serde")]
+mod repr;
+mod stats;
+mod symmetry;
+mod transform;
+pub mod validate;
+
+pub use arith::{Combine, OverflowPolicy};
+pub use cgp::{ParseError, Section};
+pub use coord::{Coord, GRID_SIZE};
+pub use diff::{PatchConflict, PatternDiff};
+pub use draw::{FloodMode, Paint};
+pub use filter::Filter;
+pub use grid::{Cell, Connectivity};
+pub use height::Height;
+pub use history::{Change, EditHistory};
+pub use merge::{MergeConflict, MergeResult, Resolution};
+pub use reach::{MovementParams, Reachability};
+pub use region::{Clipping, HeightMode, PasteOptions, Region, Stamp};
+pub use stats::PatternStats;
+pub use symmetry::{SymmetricEditor, Symmetry};
+pub use transform::Transform;
+
+const MAP_SIZE: usize = GRID_SIZE * GRID_SIZE;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Error occurred while converting map")]
+    UltraMapConversionError,
+
+    #[error("Error occurred while reading map")]
+    UltraMapParsingError(#[from] TryFromIntError),
+
+    #[error("Error occurred while writing to file")]
+    UltraMapIoError(#[from] std::io::Error),
+
+    #[error("Invalid coordinates")]
+    UltraMapIndexOutOfBounds,
+
+    #[error("Invalid character")]
+    UltraMapInvalidCharacter,
+
+    #[error("Error occurred while parsing map: {0}")]
+    UltraMapSyntaxError(#[from] ParseError),
+
+    #[error("Height must be between -50 and 50")]
+    UltraMapHeightOutOfRange,
+
+    #[error("Invalid parameter: {0}")]
+    UltraMapInvalidParameter(String),
+
+    #[error("Generation ran into a contradiction at {0}")]
+    UltraMapContradiction(Coord),
+
+    #[error("Patch does not apply, {} cells conflict", .0.len())]
+    UltraMapPatchConflict(Vec<PatchConflict>),
+
+    #[error("Expected a 16x16 grid of {0}")]
+    UltraMapInvalidGridSize(&'static str),
+
+    #[error("Error occurred while converting {0} data: {1}")]
+    UltraMapFormatError(format::Format, String),
+
+    #[error("No known format for {0}")]
+    UltraMapUnknownFormat(String),
+}
+
+impl From<Infallible> for Error {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+/// Each map is 16x16, each cell can range from -50 to 50 (0 is base height), which is enforced by `Height`.
+/// The level_map describes the height level while prefab_map indicates if and what prefabs should be placed on the cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "repr::PatternRepr", into = "repr::PatternRepr")
+)]
+pub struct MapPattern {
+    level_map: [Height; MAP_SIZE],
+    prefab_map: [Prefab; MAP_SIZE],
+}
+
+impl Default for MapPattern {
+    fn default() -> Self {
+        Self {
+            level_map: [Height::ZERO; MAP_SIZE],
+            prefab_map: [Prefab::Empty; MAP_SIZE],
+        }
+    }
+}
+
+impl MapPattern {
+    pub fn from(path: &str) -> Result<Self, Error> {
+        Self::from_path(path)
+    }
+
+    /// load a pattern from a `.cgp` file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        Self::from_reader(file)
+    }
+
+    /// read a pattern from anything implementing `Read`, e.g. an archive entry or a network stream
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut input = Vec::new();
+        reader.read_to_end(&mut input)?;
+        Self::from_bytes(&input)
+    }
+
+    /// parse a pattern from raw bytes
+    /// Invalid UTF-8 is reported as a parse error at the position of the first invalid byte.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(cgp::parse_bytes(bytes)?)
+    }
+
+    pub fn get_level_map(&self) -> &[Height] {
+        self.level_map.as_slice()
+    }
+
+    pub fn get_level_map_mut(&mut self) -> &mut [Height] {
+        self.level_map.as_mut_slice()
+    }
+
+    pub fn get_prefab_map(&self) -> &[Prefab] {
+        self.prefab_map.as_slice()
+    }
+    pub fn get_prefab_map_mut(&mut self) -> &mut [Prefab] {
+        self.prefab_map.as_mut_slice()
+    }
+
+    /// all heights on one line followed by all prefabs on the next, without row breaks
+    /// Uses the same cell encoding as the `.cgp` format, so the result can be parsed again.
+    pub fn get_map_raw(&self) -> String {
+        let mut returnee = String::new();
+        cgp::write(self, &mut returnee, cgp::Layout::Compact)
+            .expect("writing to a String cannot fail");
+        returnee
+    }
+
+    pub fn get_level(&self, at: Coord) -> Height {
+        self.level_map[at.index()]
+    }
+
+    pub fn get_prefab(&self, at: Coord) -> Prefab {
+        self.prefab_map[at.index()]
+    }
+
+    /// height level at the given column and row
+    pub fn get_level_at(&self, column: usize, row: usize) -> Result<Height, Error> {
+        Ok(self.get_level(Coord::from_column_row(column, row)?))
+    }
+
+    /// prefab at the given column and row
+    pub fn get_prefab_at(&self, column: usize, row: usize) -> Result<Prefab, Error> {
+        Ok(self.get_prefab(Coord::from_column_row(column, row)?))
+    }
+
+    /// set height level of tile, accepts a `Coord` or an `(x, y)` tuple (column, row) and a `Height` or an `i8`
+    /// Fails if the coordinates are outside the grid or the level is not within -50..=50.
+    pub fn try_set_level<C, H>(&mut self, at: C, level: H) -> Result<(), Error>
+    where
+        C: TryInto<Coord>,
+        H: TryInto<Height>,
+        Error: From<C::Error> + From<H::Error>,
+    {
+        let at = at.try_into()?;
+        self.level_map[at.index()] = level.try_into()?;
+        Ok(())
+    }
+
+    /// set prefab at given tile, accepts a `Coord` or an `(x, y)` tuple (column, row)
+    pub fn try_set_prefab<C>(&mut self, at: C, prefab: Prefab) -> Result<(), Error>
+    where
+        C: TryInto<Coord>,
+        Error: From<C::Error>,
+    {
+        let at = at.try_into()?;
+        self.prefab_map[at.index()] = prefab;
+        Ok(())
+    }
+
+    /// set height level of tile
+    /// Note! level cannot be higher than 50 or lower than -50
+    /// Coordinates are 0 indexed, `x` is the row and `y` the column (see `Coord::from_legacy`)
+    /// Panics on invalid input, see `try_set_level` for a non-panicking version.
+    #[deprecated(note = "takes (row, column), use `try_set_level` with a `Coord` instead")]
+    pub fn set_level_at(&mut self, x: usize, y: usize, level: i8) {
+        if let Err(e) = Coord::from_legacy(x, y).and_then(|at| self.try_set_level(at, level)) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Panics on invalid input, see `try_set_level` for a non-panicking version.
+    pub fn set_level_at_index(&mut self, index: usize, level: i8) {
+        if let Err(e) = Coord::from_index(index).and_then(|at| self.try_set_level(at, level)) {
+            panic!("{}", e);
+        }
+    }
+
+    /// set prefab at given tile
+    /// x and y coordinates are 0 indexed, `x` is the row and `y` the column (see `Coord::from_legacy`)
+    /// Panics on invalid input, see `try_set_prefab` for a non-panicking version.
+    #[deprecated(note = "takes (row, column), use `try_set_prefab` with a `Coord` instead")]
+    pub fn set_prefab_at(&mut self, x: usize, y: usize, prefab: Prefab) {
+        if let Err(e) = Coord::from_legacy(x, y).and_then(|at| self.try_set_prefab(at, prefab)) {
+            panic!("{}", e);
+        }
+    }
+
+    /// save the pattern as `<name>.cgp`
+    pub fn save_pattern(&self, name: &str) -> Result<(), Error> {
+        self.save_to_path(format!("{}.cgp", name))
+    }
+
+    /// save the pattern to exactly the given path, no extension is appended
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let f = File::create(path)?;
+        self.write_to(f)
+    }
+
+    /// write the `.cgp` text of the pattern to anything implementing `Write`
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        writer.write_all(self.to_cgp_string().as_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// the `.cgp` text of the pattern, identical to what `save_pattern` writes
+    pub fn to_cgp_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for MapPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        cgp::write(self, f, cgp::Layout::Grid)
+    }
+}
+
+impl FromStr for MapPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(cgp::parse(s)?)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Prefab {
+    Empty,
+    Melee,
+    #[default]
+    Projectile,
+    JumpPad,
+    Stairs,
+    Hideous,
+}
+
+impl Prefab {
+    /// every prefab variant, in declaration order
+    pub const ALL: [Prefab; 6] = [
+        Prefab::Empty,
+        Prefab::Melee,
+        Prefab::Projectile,
+        Prefab::JumpPad,
+        Prefab::Stairs,
+        Prefab::Hideous,
+    ];
+
+    pub fn match_char(prefab: &Prefab) -> char {
+        match prefab {
+            Prefab::Empty => '0',
+            Prefab::Melee => 'n',
+            Prefab::Projectile => 'p',
+            Prefab::JumpPad => 'J',
+            Prefab::Stairs => 's',
+            Prefab::Hideous => 'H',
+        }
+    }
+}
+
+impl From<Prefab> for char {
+    fn from(prefab: Prefab) -> Self {
+        match prefab {
+            Prefab::Empty => '0',
+            Prefab::Melee => 'n',
+            Prefab::Projectile => 'p',
+            Prefab::JumpPad => 'J',
+            Prefab::Stairs => 's',
+            Prefab::Hideous => 'H',
+        }
+    }
+}
+
+impl TryFrom<char> for Prefab {
+    type Error = Error;
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'n' => Ok(Prefab::Melee),
+            'p' => Ok(Prefab::Projectile),
+            'J' => Ok(Prefab::JumpPad),
+            'H' => Ok(Prefab::Hideous),
+            's' => Ok(Prefab::Stairs),
+            '0' => Ok(Prefab::Empty),
+            _ => Err(Error::UltraMapInvalidCharacter),
+        }
+    }
+}
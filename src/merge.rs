@@ -0,0 +1,132 @@
+use crate::{Cell, Coord, MapPattern};
+
+/// How a conflicting cell is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    /// the higher of both heights, with the prefab of the side it came from
+    /// If only the prefab conflicts, the side whose height change was kept wins, ours if neither changed it.
+    Max,
+    /// the lower of both heights, with the prefab of the side it came from
+    /// If only the prefab conflicts, the side whose height change was kept wins, ours if neither changed it.
+    Min,
+}
+
+/// A cell both sides changed in different ways.
+/// Height and prefab are merged separately, so only the parts that actually conflict are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub base: Cell,
+    pub ours: Cell,
+    pub theirs: Cell,
+}
+
+impl MergeConflict {
+    pub fn coord(&self) -> Coord {
+        self.base.coord
+    }
+
+    pub fn height_conflicts(&self) -> bool {
+        conflicts(self.base.height, self.ours.height, self.theirs.height)
+    }
+
+    pub fn prefab_conflicts(&self) -> bool {
+        conflicts(self.base.prefab, self.ours.prefab, self.theirs.prefab)
+    }
+
+    /// the merged cell for `resolution`, parts that don't conflict are merged as usual
+    pub fn resolve(&self, resolution: Resolution) -> Cell {
+        let take_theirs = match resolution {
+            Resolution::Ours => false,
+            Resolution::Theirs => true,
+            // the merged height is not up for choice, keep its prefab with it
+            Resolution::Max | Resolution::Min if !self.height_conflicts() => {
+                self.ours.height == self.base.height && self.theirs.height != self.base.height
+            }
+            Resolution::Max => self.theirs.height > self.ours.height,
+            Resolution::Min => self.theirs.height < self.ours.height,
+        };
+        let side = if take_theirs { self.theirs } else { self.ours };
+        Cell {
+            coord: self.coord(),
+            height: if self.height_conflicts() {
+                side.height
+            } else {
+                merge_value(self.base.height, self.ours.height, self.theirs.height)
+            },
+            prefab: if self.prefab_conflicts() {
+                side.prefab
+            } else {
+                merge_value(self.base.prefab, self.ours.prefab, self.theirs.prefab)
+            },
+        }
+    }
+}
+
+/// Result of a three-way merge, see `MapPattern::merge`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    /// the merged pattern, conflicting cells hold our side until they are resolved
+    pub merged: MapPattern,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// resolve every conflict the same way
+    pub fn resolve(self, resolution: Resolution) -> MapPattern {
+        self.resolve_with(|_| resolution)
+    }
+
+    /// resolve each conflict with the resolution `choose` picks for it
+    pub fn resolve_with(self, choose: impl Fn(&MergeConflict) -> Resolution) -> MapPattern {
+        let mut merged = self.merged;
+        for conflict in &self.conflicts {
+            let cell = conflict.resolve(choose(conflict));
+            merged[cell.coord] = cell.height;
+            merged.prefab_map[cell.coord.index()] = cell.prefab;
+        }
+        merged
+    }
+}
+
+impl MapPattern {
+    /// three-way merge of two patterns edited from `self`
+    /// Changes made on only one side, or the same change made on both sides, are merged automatically,
+    /// cells changed differently on both sides are reported as conflicts.
+    pub fn merge(&self, ours: &MapPattern, theirs: &MapPattern) -> MergeResult {
+        let mut merged = self.clone();
+        let mut conflicts = Vec::new();
+        for at in Coord::all() {
+            let conflict = MergeConflict {
+                base: self.cell(at),
+                ours: ours.cell(at),
+                theirs: theirs.cell(at),
+            };
+            let cell = conflict.resolve(Resolution::Ours);
+            merged[at] = cell.height;
+            merged.prefab_map[at.index()] = cell.prefab;
+            if conflict.height_conflicts() || conflict.prefab_conflicts() {
+                conflicts.push(conflict);
+            }
+        }
+        MergeResult { merged, conflicts }
+    }
+}
+
+fn conflicts<T: PartialEq>(base: T, ours: T, theirs: T) -> bool {
+    ours != base && theirs != base && ours != theirs
+}
+
+/// the changed side wins, `ours` if neither or both changed the same way
+fn merge_value<T: PartialEq + Copy>(base: T, ours: T, theirs: T) -> T {
+    if ours == base {
+        theirs
+    } else {
+        ours
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{Connectivity, Coord, MapPattern, Prefab, Region, GRID_SIZE, MAP_SIZE};
+
+/// How the player can move between cells, all values are in height levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovementParams {
+    /// highest step onto a neighboring cell that can be walked up
+    pub max_step_up: i8,
+    /// highest neighboring cell that can be reached with a jump
+    pub jump_height: i8,
+    /// deepest drop onto a neighboring cell that is still allowed
+    pub max_fall: i8,
+    /// highest difference a `Prefab::Stairs` cell bridges to its neighbors, in both directions
+    pub stairs_height: i8,
+    /// how high above the pad a `Prefab::JumpPad` launches the player
+    pub jump_pad_height: i8,
+    /// how many cells away (in both axes) a launch from a jump pad can land
+    pub jump_pad_range: usize,
+}
+
+impl Default for MovementParams {
+    fn default() -> Self {
+        Self {
+            max_step_up: 1,
+            jump_height: 3,
+            max_fall: 50,
+            stairs_height: 6,
+            jump_pad_height: 20,
+            jump_pad_range: 3,
+        }
+    }
+}
+
+/// Result of `MapPattern::reachability`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reachability {
+    /// cells the player can get to from the spawn
+    pub reachable: Region,
+    /// cells the player can never get to from the spawn
+    pub unreachable: Vec<Coord>,
+    /// groups of cells the player can move freely between (in both directions), largest first
+    pub components: Vec<Vec<Coord>>,
+}
+
+impl MovementParams {
+    /// whether the player can move from `from` directly to `to`
+    pub fn can_move(&self, pattern: &MapPattern, from: Coord, to: Coord) -> bool {
+        let start = pattern.cell(from);
+        let target = pattern.cell(to);
+        let rise = i32::from(target.height) - i32::from(start.height);
+        let adjacent = from.x().abs_diff(to.x()) + from.y().abs_diff(to.y()) == 1;
+
+        if adjacent {
+            let stairs = start.prefab == Prefab::Stairs || target.prefab == Prefab::Stairs;
+            let climb = if stairs {
+                self.stairs_height.max(self.jump_height)
+            } else {
+                self.max_step_up.max(self.jump_height)
+            };
+            let fall = if stairs {
+                self.stairs_height.max(self.max_fall)
+            } else {
+                self.max_fall
+            };
+            if rise <= i32::from(climb) && -rise <= i32::from(fall) {
+                return true;
+            }
+        }
+
+        start.prefab == Prefab::JumpPad
+            && from != to
+            && from.x().abs_diff(to.x()) <= self.jump_pad_range
+            && from.y().abs_diff(to.y()) <= self.jump_pad_range
+            && rise <= i32::from(self.jump_pad_height)
+    }
+
+    /// cells reachable from `from` in a single move
+    fn moves<'a>(
+        &'a self,
+        pattern: &'a MapPattern,
+        from: Coord,
+    ) -> impl Iterator<Item = Coord> + 'a {
+        let pad = pattern.get_prefab(from) == Prefab::JumpPad;
+        let range = if pad { self.jump_pad_range } else { 1 };
+        let (left, right) = (
+            from.x().saturating_sub(range),
+            from.x().saturating_add(range).min(GRID_SIZE - 1),
+        );
+        let (top, bottom) = (
+            from.y().saturating_sub(range),
+            from.y().saturating_add(range).min(GRID_SIZE - 1),
+        );
+        let nearby: Vec<Coord> = if pad {
+            (top..=bottom)
+                .flat_map(|y| (left..=right).map(move |x| Coord::new(x, y).unwrap()))
+                .collect()
+        } else {
+            from.neighbors(Connectivity::Four).collect()
+        };
+        nearby
+            .into_iter()
+            .filter(move |to| self.can_move(pattern, from, *to))
+    }
+}
+
+impl MapPattern {
+    /// which cells a player spawning at `spawn` can reach
+    pub fn reachability(&self, spawn: Coord, params: &MovementParams) -> Reachability {
+        let edges: Vec<Vec<Coord>> = Coord::all()
+            .map(|at| params.moves(self, at).collect())
+            .collect();
+
+        let mut reachable = Region::new();
+        reachable.insert(spawn);
+        let mut stack = vec![spawn];
+        while let Some(at) = stack.pop() {
+            for to in &edges[at.index()] {
+                if !reachable.contains(*to) {
+                    reachable.insert(*to);
+                    stack.push(*to);
+                }
+            }
+        }
+
+        let unreachable = Coord::all().filter(|at| !reachable.contains(*at)).collect();
+        let mut components = strongly_connected(&edges);
+        components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+
+        Reachability {
+            reachable,
+            unreachable,
+            components,
+        }
+    }
+}
+
+/// Kosaraju's algorithm, done iteratively
+fn strongly_connected(edges: &[Vec<Coord>]) -> Vec<Vec<Coord>> {
+    let mut reverse: Vec<Vec<usize>> = vec![Vec::new(); MAP_SIZE];
+    for (from, targets) in edges.iter().enumerate() {
+        for to in targets {
+            reverse[to.index()].push(from);
+        }
+    }
+
+    // order cells by the time their depth first search finishes
+    let mut visited = [false; MAP_SIZE];
+    let mut order = Vec::with_capacity(MAP_SIZE);
+    for start in 0..MAP_SIZE {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![(start, 0)];
+        while let Some((node, next)) = stack.pop() {
+            if let Some(to) = edges[node].get(next) {
+                stack.push((node, next + 1));
+                if !visited[to.index()] {
+                    visited[to.index()] = true;
+                    stack.push((to.index(), 0));
+                }
+            } else {
+                order.push(node);
+            }
+        }
+    }
+
+    let mut assigned = [false; MAP_SIZE];
+    let mut components = Vec::new();
+    for &start in order.iter().rev() {
+        if assigned[start] {
+            continue;
+        }
+        assigned[start] = true;
+        let mut component = Vec::new();
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            component.push(Coord::from_index(node).unwrap());
+            for &from in &reverse[node] {
+                if !assigned[from] {
+                    assigned[from] = true;
+                    stack.push(from);
+                }
+            }
+        }
+        component.sort_by_key(|at| at.index());
+        components.push(component);
+    }
+    components
+}
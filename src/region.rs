@@ -0,0 +1,252 @@
+use crate::{Coord, Error, Height, MapPattern, Prefab, GRID_SIZE, MAP_SIZE};
+
+/// A set of cells on the grid, e.g. a rectangle or an arbitrary selection made with the brush.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    mask: [bool; MAP_SIZE],
+}
+
+impl Default for Region {
+    fn default() -> Self {
+        Self {
+            mask: [false; MAP_SIZE],
+        }
+    }
+}
+
+impl Region {
+    /// an empty region
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the rectangle spanned by two opposite corners, both corners are included
+    pub fn rect(corner: Coord, opposite: Coord) -> Self {
+        let (left, right) = (corner.x().min(opposite.x()), corner.x().max(opposite.x()));
+        let (top, bottom) = (corner.y().min(opposite.y()), corner.y().max(opposite.y()));
+        Coord::all()
+            .filter(|at| (left..=right).contains(&at.x()) && (top..=bottom).contains(&at.y()))
+            .collect()
+    }
+
+    /// the whole grid
+    pub fn all() -> Self {
+        Coord::all().collect()
+    }
+
+    pub fn insert(&mut self, at: Coord) {
+        self.mask[at.index()] = true;
+    }
+
+    pub fn remove(&mut self, at: Coord) {
+        self.mask[at.index()] = false;
+    }
+
+    pub fn contains(&self, at: Coord) -> bool {
+        self.mask[at.index()]
+    }
+
+    /// the selected cells, in storage order
+    pub fn cells(&self) -> impl Iterator<Item = Coord> + '_ {
+        Coord::all().filter(|at| self.contains(*at))
+    }
+
+    pub fn len(&self) -> usize {
+        self.mask.iter().filter(|selected| **selected).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// top left and bottom right corner of the smallest rectangle containing the region
+    pub fn bounds(&self) -> Option<(Coord, Coord)> {
+        let mut cells = self.cells();
+        let first = cells.next()?;
+        let (mut left, mut top, mut right, mut bottom) =
+            (first.x(), first.y(), first.x(), first.y());
+        for at in cells {
+            left = left.min(at.x());
+            right = right.max(at.x());
+            top = top.min(at.y());
+            bottom = bottom.max(at.y());
+        }
+        Some((
+            Coord::new(left, top).unwrap(),
+            Coord::new(right, bottom).unwrap(),
+        ))
+    }
+
+    /// cells selected in either region
+    pub fn union(&self, other: &Region) -> Region {
+        Coord::all()
+            .filter(|at| self.contains(*at) || other.contains(*at))
+            .collect()
+    }
+
+    /// cells selected in both regions
+    pub fn intersection(&self, other: &Region) -> Region {
+        Coord::all()
+            .filter(|at| self.contains(*at) && other.contains(*at))
+            .collect()
+    }
+}
+
+impl FromIterator<Coord> for Region {
+    fn from_iter<I: IntoIterator<Item = Coord>>(iter: I) -> Self {
+        let mut region = Region::new();
+        for at in iter {
+            region.insert(at);
+        }
+        region
+    }
+}
+
+/// Cells lifted out of a pattern with `MapPattern::extract`, ready to be pasted elsewhere.
+/// The stamp covers the bounding rectangle of the region it was extracted from,
+/// cells of that rectangle outside the region are empty and are skipped when pasting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stamp {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<(Height, Prefab)>>,
+}
+
+impl Stamp {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// contents of the stamp at a position relative to its top left corner
+    pub fn get(&self, x: usize, y: usize) -> Option<(Height, Prefab)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.cells[y * self.width + x]
+    }
+
+    /// height of the lowest cell, relative pastes are measured from it
+    fn base(&self) -> Height {
+        self.cells
+            .iter()
+            .flatten()
+            .map(|(height, _)| *height)
+            .min()
+            .unwrap_or(Height::ZERO)
+    }
+}
+
+/// How the heights of a stamp are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeightMode {
+    /// the destination gets the exact heights of the stamp
+    #[default]
+    Absolute,
+    /// the stamp's shape is placed on top of the destination:
+    /// each cell is raised by its height above the lowest cell of the stamp
+    Relative,
+    /// heights are not copied
+    Skip,
+}
+
+/// What happens to stamp cells that fall outside the 16x16 grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Clipping {
+    /// cells outside the grid are dropped
+    #[default]
+    Clip,
+    /// cells outside the grid continue on the opposite side
+    Wrap,
+    /// the paste fails without changing anything
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasteOptions {
+    pub heights: HeightMode,
+    pub copy_prefabs: bool,
+    pub clipping: Clipping,
+}
+
+impl Default for PasteOptions {
+    fn default() -> Self {
+        Self {
+            heights: HeightMode::Absolute,
+            copy_prefabs: true,
+            clipping: Clipping::Clip,
+        }
+    }
+}
+
+impl MapPattern {
+    /// copy the cells of `region`, returns `None` for an empty region
+    pub fn extract(&self, region: &Region) -> Option<Stamp> {
+        let (top_left, bottom_right) = region.bounds()?;
+        let width = bottom_right.x() - top_left.x() + 1;
+        let height = bottom_right.y() - top_left.y() + 1;
+
+        let mut cells = vec![None; width * height];
+        for at in region.cells() {
+            let (x, y) = (at.x() - top_left.x(), at.y() - top_left.y());
+            cells[y * width + x] = Some((self.get_level(at), self.get_prefab(at)));
+        }
+
+        Some(Stamp {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// paste `stamp` with its top left corner at `at`, returns the cells that changed
+    pub fn paste(
+        &mut self,
+        stamp: &Stamp,
+        at: Coord,
+        options: PasteOptions,
+    ) -> Result<Vec<Coord>, Error> {
+        let mut targets = Vec::new();
+        for y in 0..stamp.height {
+            for x in 0..stamp.width {
+                let Some(content) = stamp.get(x, y) else {
+                    continue;
+                };
+                let (target_x, target_y) = (at.x() + x, at.y() + y);
+                let target = match options.clipping {
+                    Clipping::Clip => Coord::new(target_x, target_y).ok(),
+                    Clipping::Wrap => Some(Coord::new(target_x % GRID_SIZE, target_y % GRID_SIZE)?),
+                    Clipping::Reject => Some(Coord::new(target_x, target_y)?),
+                };
+                if let Some(target) = target {
+                    targets.push((target, content));
+                }
+            }
+        }
+
+        let base = stamp.base();
+        let mut changed = Vec::new();
+        for (target, (height, prefab)) in targets {
+            let before = self.cell(target);
+            match options.heights {
+                HeightMode::Absolute => self[target] = height,
+                HeightMode::Relative => {
+                    let raised = i32::from(before.height) + i32::from(height) - i32::from(base);
+                    self[target] = Height::clamped(raised);
+                }
+                HeightMode::Skip => {}
+            }
+            if options.copy_prefabs {
+                self.prefab_map[target.index()] = prefab;
+            }
+            if self.cell(target) != before && !changed.contains(&target) {
+                changed.push(target);
+            }
+        }
+        changed.sort_by_key(|at| at.index());
+        Ok(changed)
+    }
+}
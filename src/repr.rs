@@ -0,0 +1,54 @@
+//! Serde representation of patterns, enabled with the `serde` feature.
+//!
+//! A pattern is stored as a struct with two 16x16 arrays, indexed `[y][x]` like the rows of a `.cgp` file:
+//! `heights` holds the numbers -50..=50 and `prefabs` the names of the `Prefab` variants.
+//! Unknown fields are ignored so files written by newer versions with additional metadata still load.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Coord, Error, Height, MapPattern, Prefab, GRID_SIZE};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "MapPattern")]
+pub(crate) struct PatternRepr {
+    heights: Vec<Vec<Height>>,
+    prefabs: Vec<Vec<Prefab>>,
+}
+
+impl From<MapPattern> for PatternRepr {
+    fn from(pattern: MapPattern) -> Self {
+        Self {
+            heights: pattern
+                .rows()
+                .map(|row| row.iter().map(|cell| cell.height).collect())
+                .collect(),
+            prefabs: pattern
+                .rows()
+                .map(|row| row.iter().map(|cell| cell.prefab).collect())
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<PatternRepr> for MapPattern {
+    type Error = Error;
+
+    fn try_from(repr: PatternRepr) -> Result<Self, Self::Error> {
+        check_grid(&repr.heights, "heights")?;
+        check_grid(&repr.prefabs, "prefabs")?;
+
+        let mut pattern = MapPattern::default();
+        for at in Coord::all() {
+            pattern[at] = repr.heights[at.y()][at.x()];
+            pattern.prefab_map[at.index()] = repr.prefabs[at.y()][at.x()];
+        }
+        Ok(pattern)
+    }
+}
+
+fn check_grid<T>(grid: &[Vec<T>], name: &'static str) -> Result<(), Error> {
+    if grid.len() != GRID_SIZE || grid.iter().any(|row| row.len() != GRID_SIZE) {
+        return Err(Error::UltraMapInvalidGridSize(name));
+    }
+    Ok(())
+}
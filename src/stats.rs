@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use crate::{Connectivity, Coord, Height, MapPattern, Prefab, MAP_SIZE};
+
+/// Summary of a pattern, see `MapPattern::stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternStats {
+    /// number of cells per height, heights that don't occur are left out
+    pub histogram: BTreeMap<Height, usize>,
+    pub min: Height,
+    pub max: Height,
+    pub mean: f32,
+    /// average of the two middle heights, since a pattern has an even number of cells
+    pub median: f32,
+    /// number of cells per prefab, in the order of `Prefab::ALL`
+    pub prefab_counts: Vec<(Prefab, usize)>,
+    /// fraction of cells below base height
+    pub pit_fraction: f32,
+    /// number of areas of 4-connected cells with equal height
+    pub plateaus: usize,
+    /// cell count of the biggest of those areas
+    pub largest_flat_area: usize,
+}
+
+impl PatternStats {
+    pub fn prefab_count(&self, prefab: Prefab) -> usize {
+        self.prefab_counts
+            .iter()
+            .find(|(p, _)| *p == prefab)
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+impl MapPattern {
+    pub fn stats(&self) -> PatternStats {
+        let mut histogram = BTreeMap::new();
+        for level in &self.level_map {
+            *histogram.entry(*level).or_insert(0) += 1;
+        }
+
+        let mut sorted = self.level_map;
+        sorted.sort();
+        let sum: i32 = sorted.iter().map(|h| i32::from(*h)).sum();
+        let middle = MAP_SIZE / 2;
+        let median = (i32::from(sorted[middle - 1]) + i32::from(sorted[middle])) as f32 / 2.0;
+
+        let prefab_counts = Prefab::ALL
+            .iter()
+            .map(|prefab| {
+                let count = self.prefab_map.iter().filter(|p| *p == prefab).count();
+                (*prefab, count)
+            })
+            .collect();
+
+        let pits = sorted.iter().filter(|h| **h < Height::ZERO).count();
+        let areas = self.flat_areas();
+
+        PatternStats {
+            histogram,
+            min: sorted[0],
+            max: sorted[MAP_SIZE - 1],
+            mean: sum as f32 / MAP_SIZE as f32,
+            median,
+            prefab_counts,
+            pit_fraction: pits as f32 / MAP_SIZE as f32,
+            plateaus: areas.len(),
+            largest_flat_area: areas.into_iter().max().unwrap_or(0),
+        }
+    }
+
+    /// sizes of all areas of 4-connected cells with equal height
+    fn flat_areas(&self) -> Vec<usize> {
+        let mut visited = [false; MAP_SIZE];
+        let mut areas = Vec::new();
+        for start in Coord::all() {
+            if visited[start.index()] {
+                continue;
+            }
+            visited[start.index()] = true;
+            let level = self.get_level(start);
+            let mut size = 0;
+            let mut stack = vec![start];
+            while let Some(at) = stack.pop() {
+                size += 1;
+                for neighbor in at.neighbors(Connectivity::Four) {
+                    if !visited[neighbor.index()] && self.get_level(neighbor) == level {
+                        visited[neighbor.index()] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            areas.push(size);
+        }
+        areas
+    }
+}
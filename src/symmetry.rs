@@ -0,0 +1,128 @@
+use crate::{Coord, Error, Height, MapPattern, Prefab, Transform};
+
+/// Symmetries a pattern can have, each is a set of transforms that map the pattern onto itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// left and right half mirror each other
+    Horizontal,
+    /// top and bottom half mirror each other
+    Vertical,
+    /// mirrored along both axes
+    BothAxes,
+    /// unchanged by quarter turns
+    Rotational4,
+    /// mirrored along the diagonal from the top left to the bottom right corner
+    Diagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 5] = [
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::BothAxes,
+        Symmetry::Rotational4,
+        Symmetry::Diagonal,
+    ];
+
+    /// the transforms generating this symmetry
+    pub fn transforms(self) -> &'static [Transform] {
+        match self {
+            Symmetry::Horizontal => &[Transform::FlipHorizontal],
+            Symmetry::Vertical => &[Transform::FlipVertical],
+            Symmetry::BothAxes => &[
+                Transform::FlipHorizontal,
+                Transform::FlipVertical,
+                Transform::Rotate180,
+            ],
+            Symmetry::Rotational4 => &[
+                Transform::Rotate90,
+                Transform::Rotate180,
+                Transform::Rotate270,
+            ],
+            Symmetry::Diagonal => &[Transform::Transpose],
+        }
+    }
+
+    /// `at` and every cell it is mirrored to, without duplicates
+    pub fn orbit(self, at: Coord) -> Vec<Coord> {
+        let mut orbit = vec![at];
+        for transform in self.transforms() {
+            let to = transform.apply(at);
+            if !orbit.contains(&to) {
+                orbit.push(to);
+            }
+        }
+        orbit
+    }
+
+    /// whether `pattern` is unchanged by this symmetry
+    pub fn holds_for(self, pattern: &MapPattern) -> bool {
+        self.transforms()
+            .iter()
+            .all(|transform| pattern.transformed(*transform) == *pattern)
+    }
+}
+
+impl MapPattern {
+    /// every symmetry the pattern already has
+    pub fn detect_symmetry(&self) -> Vec<Symmetry> {
+        Symmetry::ALL
+            .into_iter()
+            .filter(|symmetry| symmetry.holds_for(self))
+            .collect()
+    }
+}
+
+/// Wraps a pattern so that every edit is mirrored according to a `Symmetry`.
+/// Edits made through the editor keep a symmetric pattern symmetric, existing asymmetries are left alone.
+#[derive(Debug)]
+pub struct SymmetricEditor<'a> {
+    pattern: &'a mut MapPattern,
+    symmetry: Symmetry,
+}
+
+impl<'a> SymmetricEditor<'a> {
+    pub fn new(pattern: &'a mut MapPattern, symmetry: Symmetry) -> Self {
+        Self { pattern, symmetry }
+    }
+
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    pub fn set_symmetry(&mut self, symmetry: Symmetry) {
+        self.symmetry = symmetry;
+    }
+
+    pub fn pattern(&self) -> &MapPattern {
+        self.pattern
+    }
+
+    /// set the height of `at` and all its mirrored cells, returns the cells that were set
+    pub fn set_level<C, H>(&mut self, at: C, level: H) -> Result<Vec<Coord>, Error>
+    where
+        C: TryInto<Coord>,
+        H: TryInto<Height>,
+        Error: From<C::Error> + From<H::Error>,
+    {
+        let level = level.try_into()?;
+        let orbit = self.symmetry.orbit(at.try_into()?);
+        for at in &orbit {
+            self.pattern[*at] = level;
+        }
+        Ok(orbit)
+    }
+
+    /// set the prefab of `at` and all its mirrored cells, returns the cells that were set
+    pub fn set_prefab<C>(&mut self, at: C, prefab: Prefab) -> Result<Vec<Coord>, Error>
+    where
+        C: TryInto<Coord>,
+        Error: From<C::Error>,
+    {
+        let orbit = self.symmetry.orbit(at.try_into()?);
+        for at in &orbit {
+            self.pattern.prefab_map[at.index()] = prefab;
+        }
+        Ok(orbit)
+    }
+}
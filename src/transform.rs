@@ -0,0 +1,94 @@
+use crate::{Coord, MapPattern, GRID_SIZE};
+
+const LAST: usize = GRID_SIZE - 1;
+
+/// Rotations and mirrors of the 16x16 grid.
+/// Rotations are clockwise as the pattern appears in the `.cgp` text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// mirror left and right
+    FlipHorizontal,
+    /// mirror top and bottom
+    FlipVertical,
+    /// mirror along the diagonal from the top left to the bottom right corner
+    Transpose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 6] = [
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+    ];
+
+    /// where the cell at `at` ends up
+    pub fn apply(self, at: Coord) -> Coord {
+        let (x, y) = (at.x(), at.y());
+        let (x, y) = match self {
+            Transform::Rotate90 => (LAST - y, x),
+            Transform::Rotate180 => (LAST - x, LAST - y),
+            Transform::Rotate270 => (y, LAST - x),
+            Transform::FlipHorizontal => (LAST - x, y),
+            Transform::FlipVertical => (x, LAST - y),
+            Transform::Transpose => (y, x),
+        };
+        Coord::new(x, y).unwrap()
+    }
+
+    /// the transform undoing this one
+    pub fn inverse(self) -> Transform {
+        match self {
+            Transform::Rotate90 => Transform::Rotate270,
+            Transform::Rotate270 => Transform::Rotate90,
+            other => other,
+        }
+    }
+}
+
+impl MapPattern {
+    /// a copy of the pattern with `transform` applied, heights and prefabs move together
+    pub fn transformed(&self, transform: Transform) -> MapPattern {
+        let mut result = self.clone();
+        for at in Coord::all() {
+            let to = transform.apply(at).index();
+            result.level_map[to] = self.level_map[at.index()];
+            result.prefab_map[to] = self.prefab_map[at.index()];
+        }
+        result
+    }
+
+    /// apply `transform` in place
+    pub fn transform(&mut self, transform: Transform) {
+        *self = self.transformed(transform);
+    }
+
+    pub fn rotate_90(&mut self) {
+        self.transform(Transform::Rotate90);
+    }
+
+    pub fn rotate_180(&mut self) {
+        self.transform(Transform::Rotate180);
+    }
+
+    pub fn rotate_270(&mut self) {
+        self.transform(Transform::Rotate270);
+    }
+
+    pub fn flip_horizontal(&mut self) {
+        self.transform(Transform::FlipHorizontal);
+    }
+
+    pub fn flip_vertical(&mut self) {
+        self.transform(Transform::FlipVertical);
+    }
+
+    pub fn transpose(&mut self) {
+        self.transform(Transform::Transpose);
+    }
+}
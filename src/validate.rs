@@ -0,0 +1,328 @@
+//! Lint rules for patterns.
+//! A `Validator` runs a set of `Rule`s over a pattern and collects their diagnostics,
+//! rules can be switched off by name and custom rules can be registered next to the built-in ones.
+
+use std::{cmp::Reverse, fmt};
+
+use crate::{Connectivity, Coord, Height, MapPattern, Prefab, GRID_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found by a rule, `cells` are the cells to highlight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub cells: Vec<Coord>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]: {}", self.severity, self.rule, self.message)
+    }
+}
+
+pub trait Rule {
+    /// unique name used to enable or disable the rule
+    fn name(&self) -> &'static str;
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic>;
+}
+
+struct Entry {
+    rule: Box<dyn Rule>,
+    enabled: bool,
+}
+
+/// Runs a set of rules over patterns.
+/// `Validator::default()` contains all built-in rules, `Validator::empty()` none.
+pub struct Validator {
+    rules: Vec<Entry>,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        let mut validator = Self::empty();
+        validator.register(HeightRange::default());
+        validator.register(PrefabInPit);
+        validator.register(StairsWithoutHigherNeighbor);
+        validator.register(JumpPadUnderOverhang::default());
+        validator.register(CentralSpawn::default());
+        validator.register(WallHeight::default());
+        validator
+    }
+}
+
+impl Validator {
+    /// a validator without any rules, to run only custom ones
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// add a rule, it is enabled right away
+    /// A rule with the same name as an existing one replaces it.
+    pub fn register(&mut self, rule: impl Rule + 'static) {
+        self.rules.retain(|entry| entry.rule.name() != rule.name());
+        self.rules.push(Entry {
+            rule: Box::new(rule),
+            enabled: true,
+        });
+    }
+
+    /// returns false if there is no rule called `name`
+    pub fn enable(&mut self, name: &str) -> bool {
+        self.set_enabled(name, true)
+    }
+
+    /// returns false if there is no rule called `name`
+    pub fn disable(&mut self, name: &str) -> bool {
+        self.set_enabled(name, false)
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|entry| entry.enabled && entry.rule.name() == name)
+    }
+
+    /// names of all registered rules, enabled or not
+    pub fn rules(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|entry| entry.rule.name())
+    }
+
+    /// diagnostics of all enabled rules, most severe first
+    pub fn validate(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = self
+            .rules
+            .iter()
+            .filter(|entry| entry.enabled)
+            .flat_map(|entry| entry.rule.check(pattern))
+            .collect();
+        diagnostics.sort_by_key(|diagnostic| Reverse(diagnostic.severity));
+        diagnostics
+    }
+
+    fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self
+            .rules
+            .iter_mut()
+            .find(|entry| entry.rule.name() == name)
+        {
+            Some(entry) => {
+                entry.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl MapPattern {
+    /// run the built-in rules, see `Validator` to configure them
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        Validator::default().validate(self)
+    }
+}
+
+/// one diagnostic for every cell `check` returns a message for
+fn cell_diagnostics(
+    rule: &'static str,
+    severity: Severity,
+    check: impl Fn(Coord) -> Option<String>,
+) -> Vec<Diagnostic> {
+    Coord::all()
+        .filter_map(|at| {
+            check(at).map(|message| Diagnostic {
+                rule,
+                severity,
+                message: format!("{} at {}", message, at),
+                cells: vec![at],
+            })
+        })
+        .collect()
+}
+
+/// Heights outside `min..=max`.
+/// `Height` already keeps every cell within -50..=50, tighten the bounds to enforce a stricter range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeightRange {
+    pub min: Height,
+    pub max: Height,
+}
+
+impl Default for HeightRange {
+    fn default() -> Self {
+        Self {
+            min: Height::MIN,
+            max: Height::MAX,
+        }
+    }
+}
+
+impl Rule for HeightRange {
+    fn name(&self) -> &'static str {
+        "height-range"
+    }
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        cell_diagnostics(self.name(), Severity::Error, |at| {
+            let level = pattern.get_level(at);
+            (level < self.min || level > self.max)
+                .then(|| format!("height {} is outside {}..={}", level, self.min, self.max))
+        })
+    }
+}
+
+/// Prefabs placed on cells below base height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrefabInPit;
+
+impl Rule for PrefabInPit {
+    fn name(&self) -> &'static str {
+        "prefab-in-pit"
+    }
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        cell_diagnostics(self.name(), Severity::Warning, |at| {
+            let cell = pattern.cell(at);
+            (cell.prefab != Prefab::Empty && cell.height < Height::ZERO)
+                .then(|| format!("{:?} placed in a pit", cell.prefab))
+        })
+    }
+}
+
+/// Stairs that don't lead anywhere because no neighbor is higher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StairsWithoutHigherNeighbor;
+
+impl Rule for StairsWithoutHigherNeighbor {
+    fn name(&self) -> &'static str {
+        "stairs-without-higher-neighbor"
+    }
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        cell_diagnostics(self.name(), Severity::Warning, |at| {
+            let cell = pattern.cell(at);
+            let leads_up = pattern
+                .neighbors(at, Connectivity::Four)
+                .any(|neighbor| neighbor.height > cell.height);
+            (cell.prefab == Prefab::Stairs && !leads_up)
+                .then(|| "stairs without a higher neighbor".to_string())
+        })
+    }
+}
+
+/// Jump pads next to walls rising more than `clearance` above the pad, the launch runs into them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JumpPadUnderOverhang {
+    pub clearance: i8,
+}
+
+impl Default for JumpPadUnderOverhang {
+    fn default() -> Self {
+        Self { clearance: 20 }
+    }
+}
+
+impl Rule for JumpPadUnderOverhang {
+    fn name(&self) -> &'static str {
+        "jump-pad-under-overhang"
+    }
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        cell_diagnostics(self.name(), Severity::Warning, |at| {
+            let cell = pattern.cell(at);
+            let limit = i32::from(cell.height) + i32::from(self.clearance);
+            let blocked = pattern
+                .neighbors(at, Connectivity::Eight)
+                .any(|neighbor| i32::from(neighbor.height) > limit);
+            (cell.prefab == Prefab::JumpPad && blocked)
+                .then(|| format!("jump pad next to a wall more than {} high", self.clearance))
+        })
+    }
+}
+
+/// The `size` x `size` cells in the center, where the player spawns, must be flat and free of prefabs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CentralSpawn {
+    pub size: usize,
+}
+
+impl Default for CentralSpawn {
+    fn default() -> Self {
+        Self { size: 2 }
+    }
+}
+
+impl Rule for CentralSpawn {
+    fn name(&self) -> &'static str {
+        "central-spawn"
+    }
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        let size = self.size.clamp(1, GRID_SIZE);
+        let start = (GRID_SIZE - size) / 2;
+        let area: Vec<Coord> = Coord::all()
+            .filter(|at| {
+                (start..start + size).contains(&at.x()) && (start..start + size).contains(&at.y())
+            })
+            .collect();
+        let level = pattern.get_level(area[0]);
+        let flat = area
+            .iter()
+            .all(|at| pattern.get_level(*at) == level && pattern.get_prefab(*at) == Prefab::Empty);
+        if flat {
+            return Vec::new();
+        }
+        vec![Diagnostic {
+            rule: self.name(),
+            severity: Severity::Error,
+            message: format!("no flat {}x{} spawn area in the center", size, size),
+            cells: area,
+        }]
+    }
+}
+
+/// Cells higher than `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WallHeight {
+    pub max: Height,
+}
+
+impl Default for WallHeight {
+    fn default() -> Self {
+        Self {
+            max: Height::new(20).unwrap(),
+        }
+    }
+}
+
+impl Rule for WallHeight {
+    fn name(&self) -> &'static str {
+        "wall-height"
+    }
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        cell_diagnostics(self.name(), Severity::Info, |at| {
+            let level = pattern.get_level(at);
+            (level > self.max)
+                .then(|| format!("wall of height {} is higher than {}", level, self.max))
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use ultra_map_lib::{Combine, Coord, Error, Height, MapPattern, OverflowPolicy};
+
+fn pattern_with(levels: &[(usize, i8)]) -> MapPattern {
+    let mut pattern = MapPattern::default();
+    for &(x, level) in levels {
+        pattern.try_set_level((x, 0), level).unwrap();
+    }
+    pattern
+}
+
+fn level(pattern: &MapPattern, x: usize) -> i8 {
+    pattern.get_level(Coord::new(x, 0).unwrap()).get()
+}
+
+#[test]
+fn clamp_saturates_at_the_height_range() {
+    let mut pattern = pattern_with(&[(0, 45), (1, -45)]);
+    pattern.add_height(10, OverflowPolicy::Clamp).unwrap();
+    assert_eq!(level(&pattern, 0), 50);
+    assert_eq!(level(&pattern, 1), -35);
+
+    pattern.sub_height(100, OverflowPolicy::Clamp).unwrap();
+    assert_eq!(level(&pattern, 0), -50);
+    assert_eq!(level(&pattern, 1), -50);
+}
+
+#[test]
+fn error_leaves_the_pattern_untouched() {
+    let mut pattern = pattern_with(&[(0, 45), (1, 3)]);
+    let before = pattern.clone();
+
+    let result = pattern.add_height(10, OverflowPolicy::Error);
+    assert!(matches!(result, Err(Error::UltraMapHeightOutOfRange)));
+    assert_eq!(pattern, before);
+
+    pattern.add_height(5, OverflowPolicy::Error).unwrap();
+    assert_eq!(level(&pattern, 0), 50);
+    assert_eq!(level(&pattern, 1), 8);
+}
+
+#[test]
+fn wrap_continues_at_the_other_end() {
+    let mut pattern = pattern_with(&[(0, 50), (1, -50)]);
+    pattern.add_height(1, OverflowPolicy::Wrap).unwrap();
+    assert_eq!(level(&pattern, 0), -50);
+    assert_eq!(level(&pattern, 1), -49);
+
+    pattern.sub_height(2, OverflowPolicy::Wrap).unwrap();
+    assert_eq!(level(&pattern, 0), 49);
+    assert_eq!(level(&pattern, 1), 50);
+}
+
+#[test]
+fn extreme_amounts_do_not_overflow() {
+    for policy in [OverflowPolicy::Clamp, OverflowPolicy::Wrap] {
+        let mut pattern = pattern_with(&[(0, 5), (1, -5)]);
+        pattern.add_height(i32::MAX, policy).unwrap();
+        pattern.sub_height(i32::MAX, policy).unwrap();
+        pattern.scale_heights(Height::ZERO, 1e12, policy).unwrap();
+        pattern.scale_heights(Height::ZERO, -1e12, policy).unwrap();
+    }
+}
+
+#[test]
+fn huge_scale_factors_clamp_in_the_right_direction() {
+    let mut pattern = pattern_with(&[(0, 5), (1, -5)]);
+    pattern
+        .scale_heights(Height::ZERO, 1e12, OverflowPolicy::Clamp)
+        .unwrap();
+    assert_eq!(level(&pattern, 0), 50);
+    assert_eq!(level(&pattern, 1), -50);
+    assert_eq!(level(&pattern, 2), 0);
+
+    let mut pattern = pattern_with(&[(0, 5)]);
+    let before = pattern.clone();
+    assert!(pattern
+        .scale_heights(Height::ZERO, 1e12, OverflowPolicy::Error)
+        .is_err());
+    assert_eq!(pattern, before);
+}
+
+#[test]
+fn scale_around_a_pivot() {
+    let mut pattern = pattern_with(&[(0, 10), (1, 4)]);
+    pattern
+        .scale_heights(Height::new(4).unwrap(), 2.0, OverflowPolicy::Error)
+        .unwrap();
+    assert_eq!(level(&pattern, 0), 16);
+    assert_eq!(level(&pattern, 1), 4);
+    assert_eq!(level(&pattern, 2), -4);
+}
+
+#[test]
+fn non_finite_factors_are_rejected() {
+    let mut pattern = pattern_with(&[(0, 10)]);
+    let before = pattern.clone();
+    for factor in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        let result = pattern.scale_heights(Height::ZERO, factor, OverflowPolicy::Clamp);
+        assert!(matches!(result, Err(Error::UltraMapInvalidParameter(_))));
+
+        let other = pattern.clone();
+        let result = pattern.combine_heights(&other, Combine::Blend(factor), OverflowPolicy::Clamp);
+        assert!(matches!(result, Err(Error::UltraMapInvalidParameter(_))));
+    }
+    assert_eq!(pattern, before);
+}
+
+#[test]
+fn combine_follows_the_policy() {
+    let a = pattern_with(&[(0, 40), (1, 10)]);
+    let b = pattern_with(&[(0, 20), (1, -30)]);
+
+    let mut sum = a.clone();
+    sum.combine_heights(&b, Combine::Add, OverflowPolicy::Clamp)
+        .unwrap();
+    assert_eq!(level(&sum, 0), 50);
+    assert_eq!(level(&sum, 1), -20);
+
+    let mut sum = a.clone();
+    sum.combine_heights(&b, Combine::Add, OverflowPolicy::Wrap)
+        .unwrap();
+    assert_eq!(level(&sum, 0), -41);
+
+    let mut sum = a.clone();
+    assert!(sum
+        .combine_heights(&b, Combine::Add, OverflowPolicy::Error)
+        .is_err());
+    assert_eq!(sum, a);
+
+    let mut blend = a.clone();
+    blend
+        .combine_heights(&b, Combine::Blend(0.25), OverflowPolicy::Error)
+        .unwrap();
+    assert_eq!(level(&blend, 0), 35);
+    assert_eq!(level(&blend, 1), 0);
+}
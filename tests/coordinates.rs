@@ -0,0 +1,65 @@
+use ultra_map_lib::{Coord, Height, MapPattern, Prefab};
+
+/// Character at `column` on line `row` of the height grid, only valid while all heights are single digits.
+fn height_char(pattern: &MapPattern, column: usize, row: usize) -> char {
+    let text = pattern.to_cgp_string();
+    text.lines().nth(row).unwrap().chars().nth(column).unwrap()
+}
+
+#[test]
+fn x_is_the_column_and_y_is_the_row_of_the_cgp_text() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((3, 1), 7).unwrap();
+
+    assert_eq!(height_char(&pattern, 3, 1), '7');
+    assert_eq!(height_char(&pattern, 1, 3), '0');
+    assert_eq!(pattern.get_level_at(3, 1).unwrap(), Height::new(7).unwrap());
+    assert_eq!(pattern[(3, 1)], Height::new(7).unwrap());
+}
+
+#[test]
+fn index_is_row_major() {
+    let at = Coord::new(3, 1).unwrap();
+    assert_eq!(at.index(), 16 + 3);
+    assert_eq!(Coord::from_index(19).unwrap(), at);
+    assert_eq!((at.column(), at.row()), (3, 1));
+}
+
+#[test]
+fn rows_and_columns_follow_the_convention() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_prefab((5, 2), Prefab::Stairs).unwrap();
+
+    let row = pattern.rows().nth(2).unwrap();
+    assert_eq!(row[5].prefab, Prefab::Stairs);
+    assert!(row.iter().all(|cell| cell.coord.y() == 2));
+
+    let column = pattern.columns().nth(5).unwrap();
+    assert_eq!(column[2].prefab, Prefab::Stairs);
+    assert!(column.iter().all(|cell| cell.coord.x() == 5));
+}
+
+#[test]
+#[allow(deprecated)]
+fn legacy_setters_keep_the_row_column_order() {
+    let mut legacy = MapPattern::default();
+    legacy.set_level_at(1, 3, 7);
+    legacy.set_prefab_at(1, 3, Prefab::JumpPad);
+
+    let mut current = MapPattern::default();
+    let at = Coord::from_legacy(1, 3).unwrap();
+    current.try_set_level(at, 7).unwrap();
+    current.try_set_prefab(at, Prefab::JumpPad).unwrap();
+
+    assert_eq!(legacy, current);
+    assert_eq!(at, Coord::new(3, 1).unwrap());
+    assert_eq!(height_char(&legacy, 3, 1), '7');
+}
+
+#[test]
+fn out_of_bounds_coordinates_are_rejected() {
+    assert!(Coord::new(16, 0).is_err());
+    assert!(Coord::new(0, 16).is_err());
+    assert!(Coord::from_index(256).is_err());
+    assert!(MapPattern::default().get_level_at(0, 16).is_err());
+}
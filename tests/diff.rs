@@ -0,0 +1,94 @@
+use ultra_map_lib::{Coord, Error, Height, MapPattern, Prefab};
+
+fn base() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((4, 4), 3).unwrap();
+    pattern
+}
+
+fn edited() -> MapPattern {
+    let mut pattern = base();
+    pattern.try_set_level((0, 0), 5).unwrap();
+    pattern.try_set_level((1, 0), -3).unwrap();
+    pattern.try_set_prefab((2, 0), Prefab::Stairs).unwrap();
+    pattern.try_set_level((4, 4), 0).unwrap();
+    pattern
+}
+
+#[test]
+fn diff_lists_changed_cells() {
+    let diff = base().diff(&edited());
+    assert_eq!(diff.len(), 4);
+    assert!(base().diff(&base()).is_empty());
+    assert_eq!(diff.height_delta(Coord::new(0, 0).unwrap()), 5);
+    assert_eq!(diff.height_delta(Coord::new(4, 4).unwrap()), -3);
+    assert_eq!(diff.height_delta(Coord::new(2, 0).unwrap()), 0);
+    assert!(diff.get(Coord::new(9, 9).unwrap()).is_none());
+}
+
+#[test]
+fn clean_apply() {
+    let diff = base().diff(&edited());
+    let mut target = base();
+    diff.apply(&mut target).unwrap();
+    assert_eq!(target, edited());
+
+    // cells the diff doesn't touch may differ
+    let mut target = base();
+    target.try_set_level((15, 15), 9).unwrap();
+    diff.apply(&mut target).unwrap();
+    assert_eq!(
+        target.get_level_at(15, 15).unwrap(),
+        Height::new(9).unwrap()
+    );
+    assert_eq!(target.get_level_at(0, 0).unwrap(), Height::new(5).unwrap());
+}
+
+#[test]
+fn conflicts_leave_the_target_untouched() {
+    let diff = base().diff(&edited());
+    let mut target = base();
+    target.try_set_level((1, 0), 7).unwrap();
+    target.try_set_prefab((2, 0), Prefab::Melee).unwrap();
+    let before = target.clone();
+
+    match diff.apply(&mut target) {
+        Err(Error::UltraMapPatchConflict(conflicts)) => {
+            let coords: Vec<Coord> = conflicts.iter().map(|c| c.found.coord).collect();
+            assert_eq!(
+                coords,
+                vec![Coord::new(1, 0).unwrap(), Coord::new(2, 0).unwrap()]
+            );
+            assert_eq!(conflicts[0].expected.height, Height::ZERO);
+            assert_eq!(conflicts[0].found.height, Height::new(7).unwrap());
+            assert_eq!(conflicts[1].found.prefab, Prefab::Melee);
+        }
+        other => panic!("expected a patch conflict, got {:?}", other),
+    }
+    assert_eq!(target, before);
+}
+
+#[test]
+fn reversed_undoes_the_diff() {
+    let diff = base().diff(&edited());
+    assert_eq!(diff.reversed(), edited().diff(&base()));
+    assert_eq!(diff.reversed().reversed(), diff);
+
+    let mut target = edited();
+    diff.reversed().apply(&mut target).unwrap();
+    assert_eq!(target, base());
+}
+
+#[test]
+fn display_marks_each_kind_of_change() {
+    let text = base().diff(&edited()).to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 16);
+    assert_eq!(lines[0], "+-*.............");
+    assert_eq!(lines[4], "....-...........");
+    assert!(lines
+        .iter()
+        .enumerate()
+        .filter(|(y, _)| ![0, 4].contains(y))
+        .all(|(_, line)| *line == "................"));
+}
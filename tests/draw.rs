@@ -0,0 +1,134 @@
+use ultra_map_lib::{Coord, FloodMode, Height, MapPattern, Paint, Prefab};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+fn h(level: i8) -> Height {
+    Height::new(level).unwrap()
+}
+
+#[test]
+fn circles_with_huge_radii_cover_the_grid() {
+    let center = at(3, 12);
+    let paint = Paint::height(h(4));
+
+    let mut pattern = MapPattern::default();
+    assert_eq!(pattern.fill_circle(center, usize::MAX, paint).len(), 256);
+
+    let mut pattern = MapPattern::default();
+    let outline = pattern.draw_circle(center, usize::MAX, paint);
+    assert_eq!(outline.len(), 60);
+}
+
+#[test]
+fn radius_zero_is_a_single_cell() {
+    let center = at(7, 7);
+    let mut pattern = MapPattern::default();
+    let cells = pattern.fill_circle(center, 0, Paint::height(h(1)));
+    assert_eq!(cells, vec![center]);
+}
+
+#[test]
+fn paint_returns_only_changed_cells() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((0, 0), 5).unwrap();
+
+    let changed = pattern.paint([at(1, 0), at(0, 0), at(1, 0)], Paint::height(h(5)));
+    assert_eq!(changed, vec![at(1, 0)]);
+    assert!(pattern
+        .paint([at(0, 0), at(1, 0)], Paint::height(h(5)))
+        .is_empty());
+    assert!(pattern.paint([at(2, 0)], Paint::default()).is_empty());
+
+    // height and prefab are changed independently
+    let changed = pattern.paint([at(0, 0), at(2, 0)], Paint::both(h(5), Prefab::Stairs));
+    assert_eq!(changed, vec![at(0, 0), at(2, 0)]);
+    assert_eq!(pattern.get_prefab(at(2, 0)), Prefab::Stairs);
+}
+
+#[test]
+fn fill_rect_accepts_corners_in_any_order() {
+    let mut a = MapPattern::default();
+    let mut b = MapPattern::default();
+    let paint = Paint::prefab(Prefab::Melee);
+    let changed = a.fill_rect(at(5, 6), at(2, 3), paint);
+    assert_eq!(changed.len(), 16);
+    assert_eq!(changed, b.fill_rect(at(2, 6), at(5, 3), paint));
+    assert_eq!(a, b);
+    assert!(changed.contains(&at(2, 3)) && changed.contains(&at(5, 6)));
+    assert!(!changed.contains(&at(6, 6)));
+}
+
+#[test]
+fn shallow_line() {
+    let mut pattern = MapPattern::default();
+    let cells = pattern.draw_line(at(0, 0), at(6, 2), Paint::height(h(1)));
+    assert_eq!(
+        cells,
+        vec![
+            at(0, 0),
+            at(1, 0),
+            at(2, 1),
+            at(3, 1),
+            at(4, 1),
+            at(5, 2),
+            at(6, 2)
+        ]
+    );
+}
+
+#[test]
+fn steep_line() {
+    let mut pattern = MapPattern::default();
+    let cells = pattern.draw_line(at(0, 0), at(2, 6), Paint::height(h(1)));
+    assert_eq!(
+        cells,
+        vec![
+            at(0, 0),
+            at(0, 1),
+            at(1, 2),
+            at(1, 3),
+            at(1, 4),
+            at(2, 5),
+            at(2, 6)
+        ]
+    );
+
+    let mut pattern = MapPattern::default();
+    let cells = pattern.draw_line(at(4, 4), at(4, 4), Paint::height(h(1)));
+    assert_eq!(cells, vec![at(4, 4)]);
+}
+
+/// left half at height 0, right half at height 1, melee prefabs along the top row
+fn halves() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    for x in 8..16 {
+        pattern.fill_rect(at(x, 0), at(x, 15), Paint::height(h(1)));
+    }
+    pattern.fill_rect(at(0, 0), at(15, 0), Paint::prefab(Prefab::Melee));
+    pattern
+}
+
+#[test]
+fn flood_fill_by_height() {
+    let mut pattern = halves();
+    let changed = pattern.flood_fill(at(0, 5), FloodMode::Height, Paint::height(h(3)));
+    assert_eq!(changed.len(), 128);
+    assert!(changed.iter().all(|c| c.x() < 8));
+}
+
+#[test]
+fn flood_fill_by_prefab() {
+    let mut pattern = halves();
+    let changed = pattern.flood_fill(at(3, 0), FloodMode::Prefab, Paint::prefab(Prefab::Stairs));
+    assert_eq!(changed.len(), 16);
+    assert!(changed.iter().all(|c| c.y() == 0));
+}
+
+#[test]
+fn flood_fill_by_both() {
+    let mut pattern = halves();
+    let changed = pattern.flood_fill(at(3, 0), FloodMode::Both, Paint::height(h(2)));
+    assert_eq!(changed, (0..8).map(|x| at(x, 0)).collect::<Vec<_>>());
+}
@@ -0,0 +1,127 @@
+use ultra_map_lib::{Coord, Filter, MapPattern, Prefab};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+fn level(pattern: &MapPattern, x: usize, y: usize) -> i8 {
+    pattern.get_level(at(x, y)).get()
+}
+
+fn spike(height: i8) -> MapPattern {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((5, 5), height).unwrap();
+    pattern
+}
+
+#[test]
+fn box_blur_spreads_a_spike() {
+    let mut pattern = spike(9);
+    let changed = pattern.apply_filter(Filter::BoxBlur { radius: 1 }, false);
+    assert_eq!(changed.len(), 9);
+    for y in 4..=6 {
+        for x in 4..=6 {
+            assert_eq!(level(&pattern, x, y), 1);
+        }
+    }
+    assert_eq!(level(&pattern, 7, 5), 0);
+}
+
+#[test]
+fn huge_radii_use_the_whole_grid() {
+    let mut pattern = spike(9);
+    let changed = pattern.apply_filter(Filter::BoxBlur { radius: usize::MAX }, false);
+    assert_eq!(changed, vec![at(5, 5)]);
+    assert_eq!(pattern, MapPattern::default());
+
+    for filter in [
+        Filter::Median { radius: usize::MAX },
+        Filter::Erode { radius: usize::MAX },
+    ] {
+        let mut pattern = spike(9);
+        pattern.apply_filter(filter, false);
+        assert_eq!(pattern, MapPattern::default());
+    }
+
+    let mut pattern = spike(9);
+    pattern.apply_filter(Filter::Dilate { radius: usize::MAX }, false);
+    assert!(pattern.get_level_map().iter().all(|h| h.get() == 9));
+}
+
+#[test]
+fn median_removes_a_lone_spike() {
+    let mut pattern = spike(9);
+    let changed = pattern.apply_filter(Filter::Median { radius: 1 }, false);
+    assert_eq!(changed, vec![at(5, 5)]);
+    assert_eq!(pattern, MapPattern::default());
+}
+
+#[test]
+fn terrace_snaps_to_even_steps() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((0, 0), 4).unwrap();
+    pattern.try_set_level((1, 0), 10).unwrap();
+    pattern.try_set_level((2, 0), 2).unwrap();
+
+    pattern.apply_filter(Filter::Terrace { levels: 3 }, false);
+    assert_eq!(level(&pattern, 0, 0), 5);
+    assert_eq!(level(&pattern, 1, 0), 10);
+    assert_eq!(level(&pattern, 2, 0), 0);
+
+    pattern.apply_filter(Filter::Terrace { levels: 1 }, false);
+    assert_eq!(pattern, MapPattern::default());
+}
+
+#[test]
+fn erode_and_dilate_are_min_and_max() {
+    let mut plateau = MapPattern::default();
+    for y in 4..=6 {
+        for x in 4..=6 {
+            plateau.try_set_level((x, y), 5).unwrap();
+        }
+    }
+
+    let mut eroded = plateau.clone();
+    eroded.apply_filter(Filter::Erode { radius: 1 }, false);
+    assert_eq!(eroded, spike(5));
+
+    let mut dilated = spike(5);
+    dilated.apply_filter(Filter::Dilate { radius: 1 }, false);
+    assert_eq!(dilated, plateau);
+}
+
+#[test]
+fn remove_spikes_respects_the_threshold() {
+    let mut pattern = spike(9);
+    pattern.try_set_level((10, 10), -2).unwrap();
+    let changed = pattern.apply_filter(Filter::RemoveSpikes { threshold: 2 }, false);
+    assert_eq!(changed, vec![at(5, 5)]);
+    assert_eq!(level(&pattern, 5, 5), 0);
+    assert_eq!(level(&pattern, 10, 10), -2);
+}
+
+#[test]
+fn preserve_prefabs_keeps_heights_under_prefabs() {
+    let mut pattern = spike(9);
+    pattern.try_set_prefab((5, 5), Prefab::JumpPad).unwrap();
+
+    let mut smoothed = pattern.clone();
+    let changed = smoothed.apply_filter(Filter::RemoveSpikes { threshold: 2 }, true);
+    assert!(changed.is_empty());
+    assert_eq!(smoothed, pattern);
+
+    // the preserved cell still counts as a neighbor
+    let mut dilated = pattern.clone();
+    dilated.apply_filter(Filter::Dilate { radius: 1 }, true);
+    assert_eq!(level(&dilated, 5, 5), 9);
+    assert_eq!(level(&dilated, 4, 4), 9);
+
+    // with a prefab on every cell nothing changes
+    let mut blocked = spike(9);
+    blocked.get_prefab_map_mut().fill(Prefab::Projectile);
+    let before = blocked.clone();
+    assert!(blocked
+        .apply_filter(Filter::BoxBlur { radius: 1 }, true)
+        .is_empty());
+    assert_eq!(blocked, before);
+}
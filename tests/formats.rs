@@ -0,0 +1,77 @@
+use ultra_map_lib::{format::Format, MapPattern, Prefab};
+
+fn sample() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((0, 0), -50).unwrap();
+    pattern.try_set_level((15, 3), 50).unwrap();
+    pattern.try_set_prefab((4, 9), Prefab::JumpPad).unwrap();
+    pattern.try_set_prefab((15, 15), Prefab::Hideous).unwrap();
+    pattern
+}
+
+#[test]
+fn every_format_round_trips() {
+    let pattern = sample();
+    for format in Format::ALL {
+        let encoded = format.encode(&pattern).unwrap();
+        assert_eq!(format.decode(&encoded).unwrap(), pattern, "{}", format);
+    }
+}
+
+#[test]
+fn formats_are_detected_by_extension() {
+    assert_eq!(Format::from_path("arena.cgp"), Some(Format::Cgp));
+    assert_eq!(Format::from_extension(".CGP"), Some(Format::Cgp));
+    assert_eq!(Format::from_path("arena.txt"), None);
+    assert_eq!(Format::from_path("arena"), None);
+    for format in Format::ALL {
+        assert_eq!(Format::from_extension(format.extension()), Some(*format));
+    }
+}
+
+#[test]
+fn load_and_save_pick_the_format_from_the_path() {
+    let pattern = sample();
+    for format in Format::ALL {
+        let path = std::env::temp_dir().join(format!(
+            "ultra_map_lib_formats_{}.{}",
+            std::process::id(),
+            format.extension()
+        ));
+        ultra_map_lib::format::save(&pattern, &path).unwrap();
+        let loaded = ultra_map_lib::format::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, pattern, "{}", format);
+    }
+    assert!(ultra_map_lib::format::load("arena.txt").is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_uses_rows_of_heights_and_prefab_names() {
+    let json = Format::Json.encode(&sample()).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["heights"][0][0], -50);
+    assert_eq!(value["heights"][3][15], 50);
+    assert_eq!(value["prefabs"][9][4], "JumpPad");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_rejects_invalid_patterns() {
+    let json = Format::Json.encode(&sample()).unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["heights"][1][1] = 51.into();
+    assert!(Format::Json.decode(&value.to_string()).is_err());
+
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["prefabs"].as_array_mut().unwrap().pop();
+    assert!(Format::Json.decode(&value.to_string()).is_err());
+
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["author"] = "someone".into();
+    assert_eq!(
+        Format::Json.decode(&value.to_string()).unwrap()[(15, 3)],
+        ultra_map_lib::Height::MAX
+    );
+}
@@ -0,0 +1,61 @@
+use ultra_map_lib::generate::{presets, NoiseGenerator, PatternGenerator};
+use ultra_map_lib::Symmetry;
+
+#[test]
+fn same_seed_gives_the_same_pattern() {
+    for generator in presets() {
+        for seed in [0, 1, 42, u64::MAX] {
+            assert_eq!(
+                generator.generate(seed).unwrap(),
+                generator.generate(seed).unwrap(),
+                "{} with seed {}",
+                generator.name(),
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn parameters_can_be_set_to_their_bounds() {
+    for mut generator in presets() {
+        for parameter in generator.parameters() {
+            for value in [parameter.min, parameter.max] {
+                generator.set_parameter(parameter.name, value).unwrap();
+                let current = generator
+                    .parameters()
+                    .into_iter()
+                    .find(|p| p.name == parameter.name)
+                    .unwrap();
+                assert_eq!(current.value, value, "{}", parameter.name);
+            }
+            assert!(generator
+                .set_parameter(parameter.name, parameter.max + 1.0)
+                .is_err());
+        }
+        assert!(generator.set_parameter("no such parameter", 0.0).is_err());
+    }
+}
+
+#[test]
+fn noise_symmetry_is_a_parameter() {
+    let mut generator = NoiseGenerator::default();
+    let names: Vec<&str> = generator.parameters().iter().map(|p| p.name).collect();
+    assert!(names.contains(&"symmetry"));
+
+    for (index, symmetry) in Symmetry::ALL.into_iter().enumerate() {
+        generator
+            .set_parameter("symmetry", (index + 1) as f32)
+            .unwrap();
+        assert_eq!(generator.symmetry, Some(symmetry));
+        let pattern = generator.generate(3).unwrap();
+        assert!(
+            pattern.detect_symmetry().contains(&symmetry),
+            "{:?}",
+            symmetry
+        );
+    }
+
+    generator.set_parameter("symmetry", 0.0).unwrap();
+    assert_eq!(generator.symmetry, None);
+}
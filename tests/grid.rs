@@ -0,0 +1,68 @@
+use ultra_map_lib::{Connectivity, Coord, Height, MapPattern, Prefab};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+fn sorted(mut coords: Vec<Coord>) -> Vec<Coord> {
+    coords.sort_by_key(|c| c.index());
+    coords
+}
+
+fn count(at: Coord, connectivity: Connectivity) -> usize {
+    at.neighbors(connectivity).count()
+}
+
+#[test]
+fn neighbor_counts() {
+    for corner in [at(0, 0), at(15, 0), at(0, 15), at(15, 15)] {
+        assert_eq!(count(corner, Connectivity::Four), 2);
+        assert_eq!(count(corner, Connectivity::Eight), 3);
+    }
+    for edge in [at(5, 0), at(0, 5), at(15, 9), at(9, 15)] {
+        assert_eq!(count(edge, Connectivity::Four), 3);
+        assert_eq!(count(edge, Connectivity::Eight), 5);
+    }
+    assert_eq!(count(at(5, 5), Connectivity::Four), 4);
+    assert_eq!(count(at(5, 5), Connectivity::Eight), 8);
+}
+
+#[test]
+fn neighbors_are_adjacent() {
+    let four = sorted(at(0, 0).neighbors(Connectivity::Four).collect());
+    assert_eq!(four, sorted(vec![at(1, 0), at(0, 1)]));
+
+    let eight = sorted(at(5, 5).neighbors(Connectivity::Eight).collect());
+    let expected: Vec<Coord> = (4..=6)
+        .flat_map(|y| (4..=6).map(move |x| at(x, y)))
+        .filter(|c| *c != at(5, 5))
+        .collect();
+    assert_eq!(eight, sorted(expected));
+}
+
+#[test]
+fn pattern_neighbors_carry_the_cell_contents() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((1, 0), 3).unwrap();
+    pattern.try_set_prefab((0, 1), Prefab::Stairs).unwrap();
+
+    let neighbors: Vec<_> = pattern.neighbors(at(0, 0), Connectivity::Eight).collect();
+    assert_eq!(neighbors.len(), 3);
+    for cell in neighbors {
+        assert_eq!(cell, pattern.cell(cell.coord));
+    }
+}
+
+#[test]
+fn cells_are_in_storage_order() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((3, 1), 7).unwrap();
+
+    let cells: Vec<_> = pattern.cells().collect();
+    assert_eq!(cells.len(), 256);
+    for (index, cell) in cells.iter().enumerate() {
+        assert_eq!(cell.coord, Coord::from_index(index).unwrap());
+    }
+    assert_eq!(cells[at(3, 1).index()].height, Height::new(7).unwrap());
+    assert_eq!(Coord::all().count(), 256);
+}
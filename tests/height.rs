@@ -0,0 +1,85 @@
+use ultra_map_lib::{Error, Height};
+
+fn h(level: i8) -> Height {
+    Height::new(level).unwrap()
+}
+
+#[test]
+fn new_accepts_exactly_the_range() {
+    assert_eq!(h(-50), Height::MIN);
+    assert_eq!(h(50), Height::MAX);
+    assert_eq!(h(0), Height::ZERO);
+    for level in [-51, 51, i8::MIN, i8::MAX] {
+        assert!(matches!(
+            Height::new(level),
+            Err(Error::UltraMapHeightOutOfRange)
+        ));
+    }
+}
+
+#[test]
+fn try_from_i32() {
+    assert_eq!(Height::try_from(-50i32).unwrap(), Height::MIN);
+    assert_eq!(Height::try_from(50i32).unwrap(), Height::MAX);
+    for level in [
+        -51,
+        51,
+        i8::MIN as i32,
+        i8::MAX as i32,
+        300,
+        i32::MIN,
+        i32::MAX,
+    ] {
+        assert!(matches!(
+            Height::try_from(level),
+            Err(Error::UltraMapHeightOutOfRange)
+        ));
+    }
+}
+
+#[test]
+fn clamped() {
+    assert_eq!(Height::clamped(-50), Height::MIN);
+    assert_eq!(Height::clamped(50), Height::MAX);
+    assert_eq!(Height::clamped(-51), Height::MIN);
+    assert_eq!(Height::clamped(51), Height::MAX);
+    assert_eq!(Height::clamped(i32::MIN), Height::MIN);
+    assert_eq!(Height::clamped(i32::MAX), Height::MAX);
+    assert_eq!(Height::clamped(7), h(7));
+}
+
+#[test]
+fn checked_arithmetic() {
+    assert_eq!(h(49).checked_add(1), Some(Height::MAX));
+    assert_eq!(Height::MAX.checked_add(1), None);
+    assert_eq!(Height::MAX.checked_add(i8::MAX), None);
+    assert_eq!(Height::MIN.checked_add(i8::MAX), None);
+    assert_eq!(Height::MIN.checked_add(100), Some(Height::MAX));
+
+    assert_eq!(h(-49).checked_sub(1), Some(Height::MIN));
+    assert_eq!(Height::MIN.checked_sub(1), None);
+    assert_eq!(Height::MIN.checked_sub(i8::MAX), None);
+    assert_eq!(Height::MAX.checked_sub(i8::MIN), None);
+    assert_eq!(Height::ZERO.checked_sub(-50), Some(Height::MAX));
+}
+
+#[test]
+fn saturating_arithmetic() {
+    assert_eq!(h(49).saturating_add(1), Height::MAX);
+    assert_eq!(Height::MAX.saturating_add(1), Height::MAX);
+    assert_eq!(Height::MAX.saturating_add(i8::MAX), Height::MAX);
+    assert_eq!(Height::MAX.saturating_add(i8::MIN), Height::MIN);
+
+    assert_eq!(h(-49).saturating_sub(1), Height::MIN);
+    assert_eq!(Height::MIN.saturating_sub(1), Height::MIN);
+    assert_eq!(Height::MIN.saturating_sub(i8::MAX), Height::MIN);
+    assert_eq!(Height::MIN.saturating_sub(i8::MIN), Height::MAX);
+}
+
+#[test]
+fn negation_stays_in_range() {
+    assert_eq!(-Height::MAX, Height::MIN);
+    assert_eq!(-Height::MIN, Height::MAX);
+    assert_eq!(-Height::ZERO, Height::ZERO);
+    assert_eq!(-h(7), h(-7));
+}
@@ -0,0 +1,178 @@
+use ultra_map_lib::{Coord, EditHistory, Height, MapPattern, Prefab};
+
+fn level(pattern: &MapPattern, x: usize, y: usize) -> i8 {
+    pattern.get_level(Coord::new(x, y).unwrap()).get()
+}
+
+#[test]
+fn undo_and_redo_single_edits() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(10);
+
+    history.set_level(&mut pattern, (1, 1), 5).unwrap();
+    history
+        .set_prefab(&mut pattern, (2, 2), Prefab::Stairs)
+        .unwrap();
+    assert_eq!(history.len(), 2);
+
+    assert!(history.undo(&mut pattern));
+    assert_eq!(pattern.get_prefab_at(2, 2).unwrap(), Prefab::Empty);
+    assert_eq!(level(&pattern, 1, 1), 5);
+
+    assert!(history.undo(&mut pattern));
+    assert_eq!(pattern, MapPattern::default());
+    assert!(!history.can_undo());
+    assert!(!history.undo(&mut pattern));
+
+    assert!(history.redo(&mut pattern));
+    assert!(history.redo(&mut pattern));
+    assert_eq!(level(&pattern, 1, 1), 5);
+    assert_eq!(pattern.get_prefab_at(2, 2).unwrap(), Prefab::Stairs);
+    assert!(!history.redo(&mut pattern));
+}
+
+#[test]
+fn edits_that_change_nothing_are_not_recorded() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(10);
+
+    history.set_level(&mut pattern, (0, 0), 0).unwrap();
+    assert!(history.is_empty());
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn invalid_edits_leave_pattern_and_history_untouched() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(10);
+
+    assert!(history.set_level(&mut pattern, (16, 0), 1).is_err());
+    assert!(history.set_level(&mut pattern, (0, 0), 51).is_err());
+    assert_eq!(pattern, MapPattern::default());
+    assert!(history.is_empty());
+}
+
+#[test]
+fn grouped_edits_undo_as_one_step() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(10);
+
+    history.begin_group();
+    history.set_level(&mut pattern, (0, 0), 1).unwrap();
+    history.begin_group();
+    history.set_level(&mut pattern, (1, 0), 2).unwrap();
+    history.end_group();
+    history
+        .set_prefab(&mut pattern, (2, 0), Prefab::JumpPad)
+        .unwrap();
+    history.end_group();
+    assert_eq!(history.len(), 1);
+
+    let edited = pattern.clone();
+    assert!(history.undo(&mut pattern));
+    assert_eq!(pattern, MapPattern::default());
+    assert!(history.redo(&mut pattern));
+    assert_eq!(pattern, edited);
+}
+
+#[test]
+fn record_captures_arbitrary_edits() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(10);
+
+    history.record(&mut pattern, |pattern| {
+        pattern.get_level_map_mut().fill(Height::new(3).unwrap());
+    });
+    assert_eq!(history.len(), 1);
+
+    assert!(history.undo(&mut pattern));
+    assert_eq!(pattern, MapPattern::default());
+}
+
+#[test]
+fn new_edits_after_undo_keep_the_old_branch() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(10);
+
+    history.set_level(&mut pattern, (0, 0), 1).unwrap();
+    history.undo(&mut pattern);
+    history.set_level(&mut pattern, (0, 0), 2).unwrap();
+    history.undo(&mut pattern);
+
+    assert_eq!(history.redo_branches(), 2);
+
+    // plain redo follows the most recent branch
+    assert!(history.redo(&mut pattern));
+    assert_eq!(level(&pattern, 0, 0), 2);
+    history.undo(&mut pattern);
+
+    assert!(history.redo_branch(&mut pattern, 0));
+    assert_eq!(level(&pattern, 0, 0), 1);
+    history.undo(&mut pattern);
+
+    // redo now follows the branch that was visited last
+    assert!(history.redo(&mut pattern));
+    assert_eq!(level(&pattern, 0, 0), 1);
+    history.undo(&mut pattern);
+
+    assert!(!history.redo_branch(&mut pattern, 2));
+    assert_eq!(pattern, MapPattern::default());
+}
+
+#[test]
+fn capacity_drops_the_oldest_steps() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(2);
+
+    for (x, value) in [1, 2, 3].into_iter().enumerate() {
+        history.set_level(&mut pattern, (x, 0), value).unwrap();
+    }
+    assert_eq!(history.len(), 2);
+
+    assert!(history.undo(&mut pattern));
+    assert!(history.undo(&mut pattern));
+    assert!(!history.undo(&mut pattern));
+    assert_eq!(level(&pattern, 0, 0), 1);
+    assert_eq!(level(&pattern, 1, 0), 0);
+}
+
+#[test]
+fn capacity_drops_abandoned_branches_before_the_current_step() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(3);
+
+    for x in 0..3 {
+        history.set_level(&mut pattern, (x, 0), 1).unwrap();
+    }
+    while history.undo(&mut pattern) {}
+    history.set_level(&mut pattern, (5, 5), 9).unwrap();
+
+    assert_eq!(history.len(), 3);
+    assert!(history.can_undo());
+    assert!(history.undo(&mut pattern));
+    assert_eq!(pattern, MapPattern::default());
+
+    // what is left of the abandoned branch can still be redone
+    assert_eq!(history.redo_branches(), 2);
+    assert!(history.redo_branch(&mut pattern, 0));
+    assert!(history.redo(&mut pattern));
+    assert_eq!(level(&pattern, 0, 0), 1);
+    assert_eq!(level(&pattern, 1, 0), 1);
+    assert_eq!(level(&pattern, 2, 0), 0);
+}
+
+#[test]
+fn capacity_of_one_keeps_the_latest_step() {
+    let mut pattern = MapPattern::default();
+    let mut history = EditHistory::new(1);
+
+    history.set_level(&mut pattern, (0, 0), 1).unwrap();
+    history.undo(&mut pattern);
+    history.set_level(&mut pattern, (0, 0), 2).unwrap();
+    history.set_level(&mut pattern, (0, 0), 3).unwrap();
+
+    assert_eq!(history.len(), 1);
+    assert!(history.undo(&mut pattern));
+    assert_eq!(level(&pattern, 0, 0), 2);
+    assert!(!history.undo(&mut pattern));
+}
@@ -0,0 +1,38 @@
+use std::io::Cursor;
+
+use ultra_map_lib::MapPattern;
+
+/// a `.cgp` text with a negative height, a two digit height and a few prefabs
+fn text() -> String {
+    let mut lines = vec!["0".repeat(16); 32];
+    lines[0] = format!("(-12)(30)5{}", "0".repeat(13));
+    lines[16] = format!("Jsp{}", "0".repeat(13));
+    lines.insert(16, String::new());
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+#[test]
+fn from_reader_matches_parse() {
+    let expected: MapPattern = text().parse().unwrap();
+    let read = MapPattern::from_reader(Cursor::new(text())).unwrap();
+    assert_eq!(read, expected);
+    assert_ne!(read, MapPattern::default());
+}
+
+#[test]
+fn save_to_path_writes_exactly_the_given_path() {
+    let pattern: MapPattern = text().parse().unwrap();
+    let path = std::env::temp_dir().join(format!("ultra_map_lib_save_{}.map", std::process::id()));
+    pattern.save_to_path(&path).unwrap();
+
+    let appended = path.with_file_name(format!(
+        "{}.cgp",
+        path.file_name().unwrap().to_str().unwrap()
+    ));
+    assert!(!appended.exists());
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(written, pattern.to_cgp_string());
+    assert_eq!(written, text());
+}
@@ -0,0 +1,178 @@
+use ultra_map_lib::{Coord, Height, MapPattern, Prefab, Resolution};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+fn h(level: i8) -> Height {
+    Height::new(level).unwrap()
+}
+
+fn edit(base: &MapPattern, f: impl FnOnce(&mut MapPattern)) -> MapPattern {
+    let mut pattern = base.clone();
+    f(&mut pattern);
+    pattern
+}
+
+#[test]
+fn one_sided_changes_merge_cleanly() {
+    let base = MapPattern::default();
+    let ours = edit(&base, |p| p.try_set_level((1, 1), 4).unwrap());
+    let theirs = edit(&base, |p| {
+        p.try_set_prefab((2, 2), Prefab::Stairs).unwrap();
+        p.try_set_level((1, 2), -4).unwrap();
+    });
+
+    let result = base.merge(&ours, &theirs);
+    assert!(result.is_clean());
+    assert_eq!(result.merged.get_level(at(1, 1)), h(4));
+    assert_eq!(result.merged.get_level(at(1, 2)), h(-4));
+    assert_eq!(result.merged.get_prefab(at(2, 2)), Prefab::Stairs);
+}
+
+#[test]
+fn both_sides_changing_different_parts_of_a_cell_merge_cleanly() {
+    let base = MapPattern::default();
+    let ours = edit(&base, |p| p.try_set_level((3, 3), 2).unwrap());
+    let theirs = edit(&base, |p| p.try_set_prefab((3, 3), Prefab::Melee).unwrap());
+
+    let result = base.merge(&ours, &theirs);
+    assert!(result.is_clean());
+    assert_eq!(result.merged.cell(at(3, 3)).height, h(2));
+    assert_eq!(result.merged.cell(at(3, 3)).prefab, Prefab::Melee);
+}
+
+#[test]
+fn identical_changes_merge_cleanly() {
+    let base = MapPattern::default();
+    let change = |p: &mut MapPattern| {
+        p.try_set_level((5, 5), 9).unwrap();
+        p.try_set_prefab((5, 5), Prefab::JumpPad).unwrap();
+    };
+    let ours = edit(&base, change);
+    let theirs = edit(&base, change);
+
+    let result = base.merge(&ours, &theirs);
+    assert!(result.is_clean());
+    assert_eq!(result.merged, ours);
+}
+
+#[test]
+fn height_only_conflict() {
+    let base = MapPattern::default();
+    let ours = edit(&base, |p| p.try_set_level((0, 0), 3).unwrap());
+    let theirs = edit(&base, |p| {
+        p.try_set_level((0, 0), 6).unwrap();
+        p.try_set_prefab((0, 0), Prefab::Stairs).unwrap();
+    });
+
+    let result = base.merge(&ours, &theirs);
+    assert_eq!(result.conflicts.len(), 1);
+    let conflict = result.conflicts[0];
+    assert_eq!(conflict.coord(), at(0, 0));
+    assert!(conflict.height_conflicts());
+    assert!(!conflict.prefab_conflicts());
+    // until resolved the conflicting part holds our side
+    assert_eq!(result.merged.cell(at(0, 0)).height, h(3));
+    assert_eq!(result.merged.cell(at(0, 0)).prefab, Prefab::Stairs);
+
+    // only theirs changed the prefab, so it is kept whichever height wins
+    let expect = [
+        (Resolution::Ours, 3),
+        (Resolution::Theirs, 6),
+        (Resolution::Max, 6),
+        (Resolution::Min, 3),
+    ];
+    for (resolution, level) in expect {
+        let cell = result.clone().resolve(resolution).cell(at(0, 0));
+        assert_eq!(cell.height, h(level), "{:?}", resolution);
+        assert_eq!(cell.prefab, Prefab::Stairs, "{:?}", resolution);
+    }
+}
+
+#[test]
+fn prefab_only_conflict() {
+    let base = MapPattern::default();
+    let ours = edit(&base, |p| p.try_set_prefab((0, 0), Prefab::Melee).unwrap());
+    let theirs = edit(&base, |p| {
+        p.try_set_prefab((0, 0), Prefab::Hideous).unwrap()
+    });
+
+    let result = base.merge(&ours, &theirs);
+    assert_eq!(result.conflicts.len(), 1);
+    assert!(!result.conflicts[0].height_conflicts());
+    assert!(result.conflicts[0].prefab_conflicts());
+
+    let expect = [
+        (Resolution::Ours, Prefab::Melee),
+        (Resolution::Theirs, Prefab::Hideous),
+        // neither side changed the height, so there is nothing to compare and ours wins
+        (Resolution::Max, Prefab::Melee),
+        (Resolution::Min, Prefab::Melee),
+    ];
+    for (resolution, prefab) in expect {
+        let cell = result.clone().resolve(resolution).cell(at(0, 0));
+        assert_eq!(cell.prefab, prefab, "{:?}", resolution);
+        assert_eq!(cell.height, Height::ZERO, "{:?}", resolution);
+    }
+}
+
+#[test]
+fn prefab_conflict_follows_the_kept_height_change() {
+    let base = MapPattern::default();
+    let ours = edit(&base, |p| p.try_set_prefab((0, 0), Prefab::Melee).unwrap());
+    let theirs = edit(&base, |p| {
+        p.try_set_level((0, 0), -5).unwrap();
+        p.try_set_prefab((0, 0), Prefab::Hideous).unwrap();
+    });
+
+    let result = base.merge(&ours, &theirs);
+    assert!(!result.conflicts[0].height_conflicts());
+    for resolution in [Resolution::Max, Resolution::Min] {
+        let cell = result.clone().resolve(resolution).cell(at(0, 0));
+        assert_eq!(cell.height, h(-5), "{:?}", resolution);
+        assert_eq!(cell.prefab, Prefab::Hideous, "{:?}", resolution);
+    }
+}
+
+#[test]
+fn max_and_min_take_the_prefab_of_the_chosen_height() {
+    let base = MapPattern::default();
+    let ours = edit(&base, |p| {
+        p.try_set_level((0, 0), 8).unwrap();
+        p.try_set_prefab((0, 0), Prefab::Melee).unwrap();
+    });
+    let theirs = edit(&base, |p| {
+        p.try_set_level((0, 0), -8).unwrap();
+        p.try_set_prefab((0, 0), Prefab::JumpPad).unwrap();
+    });
+
+    let result = base.merge(&ours, &theirs);
+    let max = result.clone().resolve(Resolution::Max).cell(at(0, 0));
+    assert_eq!((max.height, max.prefab), (h(8), Prefab::Melee));
+    let min = result.clone().resolve(Resolution::Min).cell(at(0, 0));
+    assert_eq!((min.height, min.prefab), (h(-8), Prefab::JumpPad));
+}
+
+#[test]
+fn resolve_with_picks_per_conflict() {
+    let base = MapPattern::default();
+    let ours = edit(&base, |p| {
+        p.try_set_level((0, 0), 1).unwrap();
+        p.try_set_level((1, 0), 1).unwrap();
+    });
+    let theirs = edit(&base, |p| {
+        p.try_set_level((0, 0), 2).unwrap();
+        p.try_set_level((1, 0), 2).unwrap();
+    });
+
+    let merged = base.merge(&ours, &theirs).resolve_with(|conflict| {
+        if conflict.coord() == at(0, 0) {
+            Resolution::Ours
+        } else {
+            Resolution::Theirs
+        }
+    });
+    assert_eq!(merged.get_level(at(0, 0)), h(1));
+    assert_eq!(merged.get_level(at(1, 0)), h(2));
+}
@@ -0,0 +1,50 @@
+use ultra_map_lib::generate::{NoiseGenerator, NoiseKind, PatternGenerator};
+
+/// `get_map_raw` of the default noise generator with seed 42
+/// Patterns are shared by seed, these must not change between versions.
+fn golden(kind: NoiseKind) -> String {
+    let (heights, prefabs) = match kind {
+        NoiseKind::Value => (
+            "10(-2)(-2)(-3)(-4)(-3)(-3)(-2)(-2)01112410(-1)(-2)(-3)(-3)(-3)(-3)(-2)(-2)(-1)1222300(-1)(-1)(-2)(-3)(-3)(-3)(-2)(-2)022112(-1)00(-1)(-2)(-3)(-2)(-2)(-1)0011110(-1)(-1)0(-1)(-1)(-2)(-1)(-1)00010000(-1)(-1)0(-1)(-1)001(-1)(-1)000002(-2)(-2)0000110(-1)(-1)(-1)0112(-2)(-2)0111110(-2)(-1)00112(-1)(-2)(-1)000000(-1)012111(-1)(-1)(-1)00(-1)(-1)(-1)(-1)0112221(-2)(-1)000(-1)(-1)(-1)(-1)0112332(-2)(-1)(-1)0110(-1)11212331(-2)(-2)(-1)0122233222221(-2)(-3)(-2)0234432222222(-2)(-3)(-2)0234432222222(-1)(-1)(-2)(-1)023432222111",
+            "00000000000000n0000000000000000000000000000J0000000000000000000J0000000000000s00000000000000p00J00n000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000s000000000000H000000s0000000000000000000",
+        ),
+        NoiseKind::Perlin => (
+            "012110(-1)0110(-1)(-2)0022333211111(-1)(-2)(-1)0113333322211(-1)(-2)0000223322222000000(-1)22221110000100(-1)(-1)10010110(-1)000110100(-1)000200(-1)0(-1)(-1)1120(-1)00111(-1)(-1)(-2)(-2)(-2)(-1)(-1)00(-1)00011(-1)(-2)(-2)(-2)(-2)(-2)(-2)(-2)(-2)(-1)00(-1)(-1)0(-1)(-1)(-2)(-2)(-1)(-1)(-1)(-1)(-1)(-2)(-3)(-1)(-1)(-1)(-1)(-1)(-1)0(-2)(-3)(-2)(-2)(-1)10(-2)(-1)(-1)(-1)(-2)(-1)(-1)00(-1)(-2)(-3)(-2)(-1)121100(-1)(-1)(-2)(-2)0(-1)(-1)(-3)(-2)1221101(-1)(-1)(-1)(-1)000(-2)012100(-1)0011(-1)01101210(-2)(-1)(-1)01211011111(-1)(-2)(-3)(-2)",
+            "0000J000000000n00000000n00000000000s000000000000000n0000000000000000000000000s00000000000000p00J000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000s0000000000000000000s0000000000000000000",
+        ),
+        NoiseKind::Simplex => (
+            "0343332120(-1)(-1)(-3)0(-4)(-3)342123244313010(-1)2321(-1)(-2)(-1)11212113302221(-2)(-1)(-2)(-2)(-3)(-2)(-1)01230013(-2)(-3)(-3)(-2)(-3)(-4)(-3)(-2)0020(-1)021(-1)(-1)111(-3)0231(-1)(-3)(-3)(-1)00(-2)002411231(-1)(-2)(-1)(-3)(-1)(-1)(-2)01410211(-2)(-3)(-1)0(-1)(-1)(-2)(-3)(-1)12(-3)(-3)(-2)01(-1)0(-1)01(-1)1(-3)020(-3)(-3)(-2)2310(-1)11220(-1)(-3)(-3)(-1)013421201132(-2)(-3)(-2)(-2)1231014(-1)011100(-3)(-3)(-1)00(-3)(-2)(-1)31(-1)0(-1)021(-2)(-3)(-3)(-1)(-2)(-3)(-1)022(-1)(-3)(-1)0321(-1)(-3)(-1)(-2)(-2)22332(-2)(-2)0331(-1)(-4)(-2)(-2)(-1)242",
+            "0000J000000000000000000n00000000000s0000000J0000000n00000000000J0000000000000s00000000000000p00000n00000000p0000000000000Hn0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000H00000000000000000000000000",
+        ),
+    };
+    format!("{}\n{}", heights, prefabs)
+}
+
+#[test]
+fn seeds_produce_the_same_pattern_in_every_version() {
+    for kind in [NoiseKind::Value, NoiseKind::Perlin, NoiseKind::Simplex] {
+        let generator = NoiseGenerator {
+            kind,
+            ..NoiseGenerator::default()
+        };
+        assert_eq!(
+            generator.generate(42).unwrap().get_map_raw(),
+            golden(kind),
+            "{:?}",
+            kind
+        );
+    }
+}
+
+#[test]
+fn excess_octaves_are_capped() {
+    let capped = NoiseGenerator {
+        octaves: 8,
+        ..NoiseGenerator::default()
+    };
+    let huge = NoiseGenerator {
+        octaves: u32::MAX,
+        ..NoiseGenerator::default()
+    };
+    assert_eq!(huge.generate(7).unwrap(), capped.generate(7).unwrap());
+}
@@ -0,0 +1,168 @@
+use ultra_map_lib::{Error, MapPattern, ParseError, Section};
+
+const EXPECTED_HEIGHT: &str = "a digit or a parenthesized height like (-12)";
+const EXPECTED_RANGE: &str = "a height between -50 and 50";
+
+/// a valid `.cgp` text with every cell set to 0
+fn lines() -> Vec<String> {
+    let row = "0".repeat(16);
+    let mut lines = vec![row.clone(); 16];
+    lines.push(String::new());
+    lines.extend(vec![row; 16]);
+    lines
+}
+
+fn text(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// `lines()` with the cell at (1 indexed) `line` and `column` replaced
+fn with_cell(line: usize, column: usize, cell: &str) -> String {
+    let mut lines = lines();
+    lines[line - 1].replace_range(column - 1..column, cell);
+    text(&lines)
+}
+
+fn parse_error(input: &[u8]) -> ParseError {
+    match MapPattern::from_bytes(input) {
+        Err(Error::UltraMapSyntaxError(error)) => error,
+        other => panic!("expected a syntax error, got {:?}", other),
+    }
+}
+
+fn assert_error(
+    error: ParseError,
+    line: usize,
+    column: usize,
+    section: Section,
+    found: &str,
+    expected: &str,
+) {
+    assert_eq!(
+        error,
+        ParseError {
+            line,
+            column,
+            section,
+            found: found.to_string(),
+            expected: expected.to_string(),
+        }
+    );
+}
+
+#[test]
+fn valid_input_parses() {
+    assert_eq!(
+        text(&lines()).parse::<MapPattern>().unwrap(),
+        MapPattern::default()
+    );
+}
+
+#[test]
+fn bad_height_character() {
+    let error = parse_error(with_cell(3, 5, "x").as_bytes());
+    assert_error(error, 3, 5, Section::Heights, "`x`", EXPECTED_HEIGHT);
+}
+
+#[test]
+fn out_of_range_height() {
+    let error = parse_error(with_cell(2, 4, "(51)").as_bytes());
+    assert_error(error, 2, 4, Section::Heights, "`(51)`", EXPECTED_RANGE);
+
+    let error = parse_error(with_cell(2, 4, "(300)").as_bytes());
+    assert_error(error, 2, 4, Section::Heights, "`(300)`", EXPECTED_RANGE);
+}
+
+#[test]
+fn non_numeric_parenthesized_height() {
+    let error = parse_error(with_cell(1, 1, "(abc)").as_bytes());
+    assert_error(error, 1, 1, Section::Heights, "`(abc)`", EXPECTED_HEIGHT);
+}
+
+#[test]
+fn bad_prefab_character() {
+    let error = parse_error(with_cell(20, 7, "x").as_bytes());
+    assert_error(
+        error,
+        20,
+        7,
+        Section::Prefabs,
+        "`x`",
+        "a prefab character (0, n, p, J, s, H)",
+    );
+}
+
+#[test]
+fn truncated_heights() {
+    let error = parse_error(b"0000");
+    assert_error(
+        error,
+        1,
+        5,
+        Section::Heights,
+        "end of input",
+        EXPECTED_HEIGHT,
+    );
+}
+
+#[test]
+fn truncated_prefabs() {
+    let mut lines = lines();
+    lines.pop();
+    let error = parse_error(text(&lines).as_bytes());
+    assert_error(
+        error,
+        33,
+        1,
+        Section::Prefabs,
+        "end of input",
+        "a prefab character",
+    );
+}
+
+#[test]
+fn trailing_garbage() {
+    let input = text(&lines()) + "x";
+    let error = parse_error(input.as_bytes());
+    assert_error(error, 34, 1, Section::Prefabs, "`x`", "end of input");
+}
+
+#[test]
+fn error_message_names_the_position() {
+    let error = parse_error(with_cell(3, 5, "x").as_bytes());
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "line 3, column 5 (height grid): expected {}, found `x`",
+            EXPECTED_HEIGHT
+        )
+    );
+}
+
+#[test]
+fn invalid_utf8() {
+    let mut input = text(&lines()).into_bytes();
+    input[2] = 0xff;
+    let error = parse_error(&input);
+    assert_error(
+        error,
+        1,
+        3,
+        Section::Heights,
+        "invalid UTF-8",
+        EXPECTED_HEIGHT,
+    );
+
+    let mut input = text(&lines()).into_bytes();
+    let at = input.len() - 3;
+    input[at] = 0xff;
+    let error = parse_error(&input);
+    assert_error(
+        error,
+        33,
+        15,
+        Section::Prefabs,
+        "invalid UTF-8",
+        "a prefab character",
+    );
+}
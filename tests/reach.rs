@@ -0,0 +1,135 @@
+use ultra_map_lib::{Coord, MapPattern, MovementParams, Prefab};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+/// a flat pattern with the given heights along the first row
+fn row(levels: &[i8]) -> MapPattern {
+    let mut pattern = MapPattern::default();
+    for (x, level) in levels.iter().enumerate() {
+        pattern.try_set_level((x, 0), *level).unwrap();
+    }
+    pattern
+}
+
+/// a flat pattern split in two by a wall of height 10 along column 8
+fn walled() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    for y in 0..16 {
+        pattern.try_set_level((8, y), 10).unwrap();
+    }
+    pattern
+}
+
+#[test]
+fn step_up_and_jump() {
+    let walk = MovementParams {
+        jump_height: 0,
+        ..MovementParams::default()
+    };
+    let pattern = row(&[0, 1, 3]);
+    assert!(walk.can_move(&pattern, at(0, 0), at(1, 0)));
+    assert!(!walk.can_move(&pattern, at(1, 0), at(2, 0)));
+
+    let jump = MovementParams::default();
+    assert!(jump.can_move(&pattern, at(1, 0), at(2, 0)));
+    assert!(!jump.can_move(&pattern, at(0, 0), at(2, 0)));
+
+    let pattern = row(&[0, 4]);
+    assert!(!jump.can_move(&pattern, at(0, 0), at(1, 0)));
+}
+
+#[test]
+fn only_neighbors_are_reachable_on_foot() {
+    let params = MovementParams::default();
+    let pattern = MapPattern::default();
+    assert!(!params.can_move(&pattern, at(0, 0), at(1, 1)));
+    assert!(!params.can_move(&pattern, at(0, 0), at(2, 0)));
+}
+
+#[test]
+fn falls_are_limited_by_max_fall() {
+    let pattern = row(&[10, 0]);
+    let params = MovementParams::default();
+    assert!(params.can_move(&pattern, at(0, 0), at(1, 0)));
+    assert!(!params.can_move(&pattern, at(1, 0), at(0, 0)));
+
+    let careful = MovementParams {
+        max_fall: 5,
+        ..MovementParams::default()
+    };
+    assert!(!careful.can_move(&pattern, at(0, 0), at(1, 0)));
+}
+
+#[test]
+fn stairs_act_as_a_ramp() {
+    let params = MovementParams {
+        max_fall: 2,
+        ..MovementParams::default()
+    };
+    let mut pattern = row(&[0, 6, 7]);
+    assert!(!params.can_move(&pattern, at(0, 0), at(1, 0)));
+
+    // stairs on either end bridge the difference, in both directions
+    pattern.try_set_prefab((1, 0), Prefab::Stairs).unwrap();
+    assert!(params.can_move(&pattern, at(0, 0), at(1, 0)));
+    assert!(params.can_move(&pattern, at(1, 0), at(0, 0)));
+
+    pattern.try_set_prefab((1, 0), Prefab::Empty).unwrap();
+    pattern.try_set_prefab((0, 0), Prefab::Stairs).unwrap();
+    assert!(params.can_move(&pattern, at(0, 0), at(1, 0)));
+
+    let mut pattern = row(&[0, 7]);
+    pattern.try_set_prefab((0, 0), Prefab::Stairs).unwrap();
+    assert!(!params.can_move(&pattern, at(0, 0), at(1, 0)));
+}
+
+#[test]
+fn jump_pads_launch_within_range_and_height() {
+    let params = MovementParams::default();
+    let mut pattern = MapPattern::default();
+    pattern.try_set_prefab((0, 0), Prefab::JumpPad).unwrap();
+    pattern.try_set_level((3, 3), 20).unwrap();
+    pattern.try_set_level((3, 2), 21).unwrap();
+
+    assert!(params.can_move(&pattern, at(0, 0), at(3, 3)));
+    assert!(!params.can_move(&pattern, at(0, 0), at(3, 2)));
+    assert!(!params.can_move(&pattern, at(0, 0), at(4, 0)));
+    // only the pad launches
+    assert!(!params.can_move(&pattern, at(1, 0), at(3, 0)));
+}
+
+#[test]
+fn walls_split_the_pattern() {
+    let reach = walled().reachability(at(0, 0), &MovementParams::default());
+
+    assert_eq!(reach.reachable.len(), 128);
+    assert!(reach.reachable.contains(at(7, 15)));
+    assert!(!reach.reachable.contains(at(8, 0)));
+    assert_eq!(reach.unreachable.len(), 128);
+
+    // the wall can be left on either side but not climbed back onto
+    let sizes: Vec<usize> = reach.components.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![128, 112, 16]);
+}
+
+#[test]
+fn jump_pads_cross_walls() {
+    let mut pattern = walled();
+    pattern.try_set_prefab((7, 0), Prefab::JumpPad).unwrap();
+    let reach = pattern.reachability(at(0, 0), &MovementParams::default());
+    assert!(reach.unreachable.is_empty());
+}
+
+#[test]
+fn huge_jump_pad_range_covers_the_grid() {
+    let params = MovementParams {
+        jump_pad_range: usize::MAX,
+        ..MovementParams::default()
+    };
+    let mut pattern = walled();
+    pattern.try_set_prefab((0, 0), Prefab::JumpPad).unwrap();
+    let reach = pattern.reachability(at(0, 0), &params);
+    assert!(reach.unreachable.is_empty());
+}
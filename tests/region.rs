@@ -0,0 +1,208 @@
+use ultra_map_lib::{
+    Clipping, Coord, Error, Height, HeightMode, MapPattern, PasteOptions, Prefab, Region,
+};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+fn cell(pattern: &MapPattern, x: usize, y: usize) -> (i8, Prefab) {
+    (
+        pattern.get_level(at(x, y)).get(),
+        pattern.get_prefab(at(x, y)),
+    )
+}
+
+/// an L shaped selection around (1, 1), the cell at (2, 2) is a hole in its bounding box
+fn source() -> (MapPattern, Region) {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((1, 1), 5).unwrap();
+    pattern.try_set_prefab((1, 1), Prefab::Stairs).unwrap();
+    pattern.try_set_level((2, 1), 7).unwrap();
+    pattern.try_set_level((1, 2), 3).unwrap();
+    pattern.try_set_prefab((1, 2), Prefab::JumpPad).unwrap();
+    pattern.try_set_level((2, 2), 9).unwrap();
+    pattern.try_set_prefab((2, 2), Prefab::Melee).unwrap();
+    let region: Region = [at(1, 1), at(2, 1), at(1, 2)].into_iter().collect();
+    (pattern, region)
+}
+
+fn options(heights: HeightMode, copy_prefabs: bool, clipping: Clipping) -> PasteOptions {
+    PasteOptions {
+        heights,
+        copy_prefabs,
+        clipping,
+    }
+}
+
+#[test]
+fn regions() {
+    let rect = Region::rect(at(4, 6), at(2, 5));
+    assert_eq!(rect.len(), 6);
+    assert_eq!(rect.bounds(), Some((at(2, 5), at(4, 6))));
+    assert!(rect.contains(at(3, 5)));
+    assert!(!rect.contains(at(5, 5)));
+
+    let other = Region::rect(at(4, 6), at(8, 8));
+    assert_eq!(
+        rect.intersection(&other).cells().collect::<Vec<_>>(),
+        vec![at(4, 6)]
+    );
+    assert_eq!(rect.union(&other).len(), 6 + 15 - 1);
+    assert_eq!(Region::all().len(), 256);
+    assert!(Region::new().is_empty());
+    assert_eq!(Region::new().bounds(), None);
+}
+
+#[test]
+fn extract_covers_the_bounding_box() {
+    let (pattern, region) = source();
+    let stamp = pattern.extract(&region).unwrap();
+    assert_eq!((stamp.width(), stamp.height()), (2, 2));
+    assert_eq!(
+        stamp.get(0, 0),
+        Some((Height::new(5).unwrap(), Prefab::Stairs))
+    );
+    assert_eq!(
+        stamp.get(1, 0),
+        Some((Height::new(7).unwrap(), Prefab::Empty))
+    );
+    assert_eq!(
+        stamp.get(0, 1),
+        Some((Height::new(3).unwrap(), Prefab::JumpPad))
+    );
+    // the hole and anything outside the stamp
+    assert_eq!(stamp.get(1, 1), None);
+    assert_eq!(stamp.get(2, 0), None);
+}
+
+#[test]
+fn extract_of_an_empty_region_is_none() {
+    assert_eq!(MapPattern::default().extract(&Region::new()), None);
+}
+
+#[test]
+fn absolute_paste_skips_holes() {
+    let (source, region) = source();
+    let stamp = source.extract(&region).unwrap();
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((11, 11), 1).unwrap();
+
+    let changed = pattern
+        .paste(&stamp, at(10, 10), PasteOptions::default())
+        .unwrap();
+    assert_eq!(changed, vec![at(10, 10), at(11, 10), at(10, 11)]);
+    assert_eq!(cell(&pattern, 10, 10), (5, Prefab::Stairs));
+    assert_eq!(cell(&pattern, 11, 10), (7, Prefab::Empty));
+    assert_eq!(cell(&pattern, 10, 11), (3, Prefab::JumpPad));
+    assert_eq!(cell(&pattern, 11, 11), (1, Prefab::Empty));
+}
+
+#[test]
+fn relative_paste_raises_from_the_lowest_cell() {
+    let (source, region) = source();
+    let stamp = source.extract(&region).unwrap();
+    let mut pattern = MapPattern::default();
+    pattern.get_level_map_mut().fill(Height::new(2).unwrap());
+
+    pattern
+        .paste(
+            &stamp,
+            at(10, 10),
+            options(HeightMode::Relative, true, Clipping::Clip),
+        )
+        .unwrap();
+    assert_eq!(cell(&pattern, 10, 10), (4, Prefab::Stairs));
+    assert_eq!(cell(&pattern, 11, 10), (6, Prefab::Empty));
+    assert_eq!(cell(&pattern, 10, 11), (2, Prefab::JumpPad));
+    assert_eq!(cell(&pattern, 11, 11), (2, Prefab::Empty));
+}
+
+#[test]
+fn skip_heights_copies_only_prefabs() {
+    let (source, region) = source();
+    let stamp = source.extract(&region).unwrap();
+    let mut pattern = MapPattern::default();
+
+    let changed = pattern
+        .paste(
+            &stamp,
+            at(10, 10),
+            options(HeightMode::Skip, true, Clipping::Clip),
+        )
+        .unwrap();
+    assert_eq!(changed, vec![at(10, 10), at(10, 11)]);
+    assert_eq!(cell(&pattern, 10, 10), (0, Prefab::Stairs));
+    assert_eq!(cell(&pattern, 10, 11), (0, Prefab::JumpPad));
+}
+
+#[test]
+fn prefabs_can_be_left_alone() {
+    let (source, region) = source();
+    let stamp = source.extract(&region).unwrap();
+    let mut pattern = MapPattern::default();
+    pattern.try_set_prefab((11, 10), Prefab::Hideous).unwrap();
+
+    pattern
+        .paste(
+            &stamp,
+            at(10, 10),
+            options(HeightMode::Absolute, false, Clipping::Clip),
+        )
+        .unwrap();
+    assert_eq!(cell(&pattern, 10, 10), (5, Prefab::Empty));
+    assert_eq!(cell(&pattern, 11, 10), (7, Prefab::Hideous));
+    assert_eq!(cell(&pattern, 10, 11), (3, Prefab::Empty));
+}
+
+#[test]
+fn clip_drops_cells_outside_the_grid() {
+    let (source, region) = source();
+    let stamp = source.extract(&region).unwrap();
+    let mut pattern = MapPattern::default();
+
+    let changed = pattern
+        .paste(&stamp, at(15, 15), PasteOptions::default())
+        .unwrap();
+    assert_eq!(changed, vec![at(15, 15)]);
+    let mut expected = MapPattern::default();
+    expected.try_set_level((15, 15), 5).unwrap();
+    expected.try_set_prefab((15, 15), Prefab::Stairs).unwrap();
+    assert_eq!(pattern, expected);
+}
+
+#[test]
+fn wrap_continues_on_the_opposite_side() {
+    let (source, region) = source();
+    let stamp = source.extract(&region).unwrap();
+    let mut pattern = MapPattern::default();
+
+    pattern
+        .paste(
+            &stamp,
+            at(15, 15),
+            options(HeightMode::Absolute, true, Clipping::Wrap),
+        )
+        .unwrap();
+    assert_eq!(cell(&pattern, 15, 15), (5, Prefab::Stairs));
+    assert_eq!(cell(&pattern, 0, 15), (7, Prefab::Empty));
+    assert_eq!(cell(&pattern, 15, 0), (3, Prefab::JumpPad));
+    assert_eq!(cell(&pattern, 0, 0), (0, Prefab::Empty));
+}
+
+#[test]
+fn reject_leaves_the_destination_unchanged() {
+    let (source, region) = source();
+    let stamp = source.extract(&region).unwrap();
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((3, 3), 4).unwrap();
+    let before = pattern.clone();
+
+    let reject = options(HeightMode::Absolute, true, Clipping::Reject);
+    // the first stamp cell fits, the ones after it don't
+    let result = pattern.paste(&stamp, at(15, 14), reject);
+    assert!(matches!(result, Err(Error::UltraMapIndexOutOfBounds)));
+    assert_eq!(pattern, before);
+
+    assert_eq!(pattern.paste(&stamp, at(14, 14), reject).unwrap().len(), 3);
+}
@@ -0,0 +1,71 @@
+use proptest::prelude::*;
+use ultra_map_lib::{Height, MapPattern, Prefab};
+
+fn pattern() -> impl Strategy<Value = MapPattern> {
+    (
+        prop::collection::vec((-50i8..=50).prop_map(|l| Height::new(l).unwrap()), 256),
+        prop::collection::vec(prop::sample::select(Prefab::ALL.to_vec()), 256),
+    )
+        .prop_map(|(levels, prefabs)| {
+            let mut pattern = MapPattern::default();
+            pattern.get_level_map_mut().copy_from_slice(&levels);
+            pattern.get_prefab_map_mut().copy_from_slice(&prefabs);
+            pattern
+        })
+}
+
+proptest! {
+    #[test]
+    fn cgp_text_round_trips(pattern in pattern()) {
+        let text = pattern.to_cgp_string();
+        let parsed: MapPattern = text.parse().unwrap();
+        prop_assert_eq!(&parsed, &pattern);
+        prop_assert_eq!(parsed.to_cgp_string(), text);
+    }
+
+    #[test]
+    fn raw_map_round_trips(pattern in pattern()) {
+        let parsed: MapPattern = pattern.get_map_raw().parse().unwrap();
+        prop_assert_eq!(parsed, pattern);
+    }
+
+    #[test]
+    fn writer_matches_display(pattern in pattern()) {
+        let mut buffer = Vec::new();
+        pattern.write_to(&mut buffer).unwrap();
+        prop_assert_eq!(String::from_utf8(buffer.clone()).unwrap(), pattern.to_string());
+        prop_assert_eq!(MapPattern::from_bytes(&buffer).unwrap(), pattern);
+    }
+}
+
+#[test]
+fn save_pattern_round_trips_through_file() {
+    let mut pattern = MapPattern::default();
+    pattern.set_level_at_index(0, 50);
+    pattern.set_level_at_index(17, 7);
+    pattern.try_set_prefab((3, 4), Prefab::JumpPad).unwrap();
+
+    let name = std::env::temp_dir().join(format!("ultra_map_lib_roundtrip_{}", std::process::id()));
+    let name = name.to_str().unwrap();
+    pattern.save_pattern(name).unwrap();
+
+    let path = format!("{}.cgp", name);
+    let loaded = MapPattern::from(&path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, pattern);
+    assert_eq!(text, pattern.to_cgp_string());
+}
+
+#[test]
+fn negative_and_multi_digit_heights_use_parentheses() {
+    let mut pattern = MapPattern::default();
+    pattern.set_level_at_index(0, -50);
+    pattern.set_level_at_index(1, 10);
+    pattern.set_level_at_index(2, 9);
+
+    let text = pattern.to_cgp_string();
+    assert!(text.starts_with("(-50)(10)9000"));
+    assert!(pattern.get_map_raw().starts_with("(-50)(10)9000"));
+}
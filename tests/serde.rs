@@ -0,0 +1,62 @@
+#![cfg(feature = "serde")]
+
+use serde_json::{json, Value};
+use ultra_map_lib::{Height, MapPattern, Prefab};
+
+fn grid(value: Value) -> Value {
+    Value::Array(vec![Value::Array(vec![value; 16]); 16])
+}
+
+fn pattern_json() -> Value {
+    json!({ "heights": grid(json!(0)), "prefabs": grid(json!("Empty")) })
+}
+
+#[test]
+fn serde_round_trips() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((3, 1), -50).unwrap();
+    pattern.try_set_prefab((1, 3), Prefab::Hideous).unwrap();
+
+    let value = serde_json::to_value(&pattern).unwrap();
+    assert_eq!(value["heights"][1][3], -50);
+    assert_eq!(value["prefabs"][3][1], "Hideous");
+    assert_eq!(
+        serde_json::from_value::<MapPattern>(value).unwrap(),
+        pattern
+    );
+
+    assert_eq!(
+        serde_json::from_value::<MapPattern>(pattern_json()).unwrap(),
+        MapPattern::default()
+    );
+}
+
+#[test]
+fn out_of_range_heights_are_rejected() {
+    assert!(serde_json::from_value::<Height>(json!(51)).is_err());
+    assert!(serde_json::from_value::<Height>(json!(-51)).is_err());
+    assert_eq!(
+        serde_json::from_value::<Height>(json!(50)).unwrap(),
+        Height::MAX
+    );
+
+    let mut value = pattern_json();
+    value["heights"][4][2] = json!(51);
+    assert!(serde_json::from_value::<MapPattern>(value).is_err());
+}
+
+#[test]
+fn grids_must_be_16_by_16() {
+    let mut value = pattern_json();
+    value["heights"].as_array_mut().unwrap().pop();
+    let error = serde_json::from_value::<MapPattern>(value).unwrap_err();
+    assert!(error.to_string().contains("heights"), "{}", error);
+
+    let mut value = pattern_json();
+    value["prefabs"][7]
+        .as_array_mut()
+        .unwrap()
+        .push(json!("Empty"));
+    let error = serde_json::from_value::<MapPattern>(value).unwrap_err();
+    assert!(error.to_string().contains("prefabs"), "{}", error);
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+use ultra_map_lib::{Height, MapPattern, Prefab};
+
+fn h(level: i8) -> Height {
+    Height::new(level).unwrap()
+}
+
+/// two 2x2 plateaus of height 4 touching only at a corner, a two cell pit and a single pillar
+fn sample() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    for (x, y) in [
+        (0, 0),
+        (1, 0),
+        (0, 1),
+        (1, 1),
+        (2, 2),
+        (3, 2),
+        (2, 3),
+        (3, 3),
+    ] {
+        pattern.try_set_level((x, y), 4).unwrap();
+    }
+    pattern.try_set_level((10, 10), -3).unwrap();
+    pattern.try_set_level((11, 10), -3).unwrap();
+    pattern.try_set_level((15, 15), 8).unwrap();
+
+    for (x, y) in [(5, 5), (6, 5), (7, 5)] {
+        pattern.try_set_prefab((x, y), Prefab::Melee).unwrap();
+    }
+    pattern.try_set_prefab((15, 15), Prefab::JumpPad).unwrap();
+    pattern
+}
+
+#[test]
+fn height_summary() {
+    let stats = sample().stats();
+    assert_eq!(
+        stats.histogram,
+        BTreeMap::from([(h(-3), 2), (h(0), 245), (h(4), 8), (h(8), 1)])
+    );
+    assert_eq!(stats.min, h(-3));
+    assert_eq!(stats.max, h(8));
+    assert_eq!(stats.mean, 34.0 / 256.0);
+    assert_eq!(stats.median, 0.0);
+    assert_eq!(stats.pit_fraction, 2.0 / 256.0);
+}
+
+#[test]
+fn median_averages_the_two_middle_heights() {
+    let mut pattern = MapPattern::default();
+    let (low, high) = pattern.get_level_map_mut().split_at_mut(128);
+    low.fill(h(1));
+    high.fill(h(2));
+    assert_eq!(pattern.stats().median, 1.5);
+}
+
+#[test]
+fn prefab_counts() {
+    let stats = sample().stats();
+    assert_eq!(
+        stats.prefab_counts,
+        vec![
+            (Prefab::Empty, 252),
+            (Prefab::Melee, 3),
+            (Prefab::Projectile, 0),
+            (Prefab::JumpPad, 1),
+            (Prefab::Stairs, 0),
+            (Prefab::Hideous, 0),
+        ]
+    );
+    assert_eq!(stats.prefab_count(Prefab::Melee), 3);
+    assert_eq!(stats.prefab_count(Prefab::Stairs), 0);
+}
+
+#[test]
+fn plateaus_are_four_connected() {
+    let stats = sample().stats();
+    // the floor, both height 4 plateaus separately, the pit and the pillar
+    assert_eq!(stats.plateaus, 5);
+    assert_eq!(stats.largest_flat_area, 245);
+
+    let flat = MapPattern::default().stats();
+    assert_eq!(flat.plateaus, 1);
+    assert_eq!(flat.largest_flat_area, 256);
+}
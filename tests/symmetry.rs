@@ -0,0 +1,68 @@
+use ultra_map_lib::{Coord, MapPattern, Prefab, SymmetricEditor, Symmetry};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+#[test]
+fn editor_edits_keep_the_symmetry() {
+    for symmetry in Symmetry::ALL {
+        let mut pattern = MapPattern::default();
+        let mut editor = SymmetricEditor::new(&mut pattern, symmetry);
+        editor.set_level((2, 3), 7).unwrap();
+        editor.set_level((0, 15), -4).unwrap();
+        editor.set_level((9, 9), 1).unwrap();
+        editor.set_prefab((5, 1), Prefab::JumpPad).unwrap();
+        editor.set_prefab((14, 6), Prefab::Melee).unwrap();
+
+        assert!(symmetry.holds_for(editor.pattern()), "{:?}", symmetry);
+        assert!(
+            pattern.detect_symmetry().contains(&symmetry),
+            "{:?}",
+            symmetry
+        );
+    }
+}
+
+#[test]
+fn plain_edits_break_the_symmetry() {
+    let mut pattern = MapPattern::default();
+    assert_eq!(pattern.detect_symmetry(), Symmetry::ALL.to_vec());
+
+    pattern.try_set_level((2, 3), 7).unwrap();
+    assert!(pattern.detect_symmetry().is_empty());
+}
+
+#[test]
+fn both_axes_implies_each_axis() {
+    let mut pattern = MapPattern::default();
+    SymmetricEditor::new(&mut pattern, Symmetry::BothAxes)
+        .set_level((2, 3), 7)
+        .unwrap();
+    assert_eq!(
+        pattern.detect_symmetry(),
+        vec![Symmetry::Horizontal, Symmetry::Vertical, Symmetry::BothAxes]
+    );
+}
+
+#[test]
+fn edits_return_the_mirrored_cells() {
+    let mut pattern = MapPattern::default();
+    let mut editor = SymmetricEditor::new(&mut pattern, Symmetry::Horizontal);
+    assert_eq!(
+        editor.set_level((2, 3), 1).unwrap(),
+        vec![at(2, 3), at(13, 3)]
+    );
+
+    editor.set_symmetry(Symmetry::Rotational4);
+    assert_eq!(editor.set_level((2, 3), 1).unwrap().len(), 4);
+
+    editor.set_symmetry(Symmetry::Diagonal);
+    assert_eq!(
+        editor.set_prefab((4, 4), Prefab::Stairs).unwrap(),
+        vec![at(4, 4)]
+    );
+
+    assert!(editor.set_level((16, 0), 1).is_err());
+    assert!(editor.set_level((0, 0), 51).is_err());
+}
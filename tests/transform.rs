@@ -0,0 +1,86 @@
+use ultra_map_lib::{Coord, Height, MapPattern, Prefab, Transform};
+
+/// a pattern without any symmetry, every transform changes it
+fn asymmetric() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    for at in Coord::all() {
+        let i = at.index();
+        pattern[at] = Height::new((i * 7 % 101) as i8 - 50).unwrap();
+        pattern.try_set_prefab(at, Prefab::ALL[i % 6]).unwrap();
+    }
+    pattern
+}
+
+#[test]
+fn four_quarter_turns_are_the_identity() {
+    let original = asymmetric();
+    let mut pattern = original.clone();
+    for _ in 0..3 {
+        pattern.rotate_90();
+        assert_ne!(pattern, original);
+    }
+    pattern.rotate_90();
+    assert_eq!(pattern, original);
+}
+
+#[test]
+fn every_transform_changes_the_pattern_and_its_inverse_undoes_it() {
+    let original = asymmetric();
+    for transform in Transform::ALL {
+        let transformed = original.transformed(transform);
+        assert_ne!(transformed, original, "{:?}", transform);
+        assert_eq!(
+            transformed.transformed(transform.inverse()),
+            original,
+            "{:?}",
+            transform
+        );
+        for at in Coord::all() {
+            assert_eq!(transform.inverse().apply(transform.apply(at)), at);
+        }
+    }
+}
+
+#[test]
+fn rotations_compose() {
+    let original = asymmetric();
+    let twice = original
+        .transformed(Transform::Rotate90)
+        .transformed(Transform::Rotate90);
+    assert_eq!(twice, original.transformed(Transform::Rotate180));
+    assert_eq!(
+        twice.transformed(Transform::Rotate90),
+        original.transformed(Transform::Rotate270)
+    );
+}
+
+#[test]
+fn rotation_is_clockwise_in_the_cgp_text() {
+    let top_left = Coord::new(0, 0).unwrap();
+    assert_eq!(
+        Transform::Rotate90.apply(top_left),
+        Coord::new(15, 0).unwrap()
+    );
+    assert_eq!(
+        Transform::FlipHorizontal.apply(top_left),
+        Coord::new(15, 0).unwrap()
+    );
+    assert_eq!(
+        Transform::FlipVertical.apply(top_left),
+        Coord::new(0, 15).unwrap()
+    );
+    assert_eq!(
+        Transform::Transpose.apply(Coord::new(3, 1).unwrap()),
+        Coord::new(1, 3).unwrap()
+    );
+
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((0, 0), 5).unwrap();
+    pattern.try_set_prefab((0, 0), Prefab::Stairs).unwrap();
+    pattern.rotate_90();
+    assert_eq!(
+        pattern.get_level_at(15, 0).unwrap(),
+        Height::new(5).unwrap()
+    );
+    assert_eq!(pattern.get_prefab_at(15, 0).unwrap(), Prefab::Stairs);
+}
@@ -0,0 +1,221 @@
+use ultra_map_lib::validate::{
+    CentralSpawn, Diagnostic, HeightRange, JumpPadUnderOverhang, PrefabInPit, Rule, Severity,
+    StairsWithoutHigherNeighbor, Validator, WallHeight,
+};
+use ultra_map_lib::{Coord, Height, MapPattern, Prefab};
+
+fn at(x: usize, y: usize) -> Coord {
+    Coord::new(x, y).unwrap()
+}
+
+fn only(rule: impl Rule + 'static) -> Validator {
+    let mut validator = Validator::empty();
+    validator.register(rule);
+    validator
+}
+
+/// rule, severity and cells of every diagnostic
+fn summary(diagnostics: &[Diagnostic]) -> Vec<(&'static str, Severity, Vec<Coord>)> {
+    diagnostics
+        .iter()
+        .map(|d| (d.rule, d.severity, d.cells.clone()))
+        .collect()
+}
+
+#[test]
+fn flat_pattern_is_clean() {
+    assert!(MapPattern::default().validate().is_empty());
+    assert_eq!(Validator::empty().rules().count(), 0);
+    assert_eq!(Validator::default().rules().count(), 6);
+}
+
+#[test]
+fn height_range() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((1, 1), 11).unwrap();
+    pattern.try_set_level((2, 1), 10).unwrap();
+    let rule = HeightRange {
+        min: Height::new(-10).unwrap(),
+        max: Height::new(10).unwrap(),
+    };
+    assert_eq!(
+        summary(&only(rule).validate(&pattern)),
+        vec![("height-range", Severity::Error, vec![at(1, 1)])]
+    );
+}
+
+#[test]
+fn prefab_in_pit() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((2, 2), -1).unwrap();
+    pattern.try_set_prefab((2, 2), Prefab::Melee).unwrap();
+    pattern.try_set_level((3, 2), -1).unwrap();
+    assert_eq!(
+        summary(&only(PrefabInPit).validate(&pattern)),
+        vec![("prefab-in-pit", Severity::Warning, vec![at(2, 2)])]
+    );
+}
+
+#[test]
+fn stairs_without_higher_neighbor() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_prefab((3, 3), Prefab::Stairs).unwrap();
+    pattern.try_set_prefab((10, 3), Prefab::Stairs).unwrap();
+    pattern.try_set_level((11, 3), 2).unwrap();
+    // diagonal neighbors don't count
+    pattern.try_set_level((4, 4), 2).unwrap();
+    assert_eq!(
+        summary(&only(StairsWithoutHigherNeighbor).validate(&pattern)),
+        vec![(
+            "stairs-without-higher-neighbor",
+            Severity::Warning,
+            vec![at(3, 3)]
+        )]
+    );
+}
+
+#[test]
+fn jump_pad_under_overhang() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_prefab((5, 5), Prefab::JumpPad).unwrap();
+    pattern.try_set_level((6, 6), 21).unwrap();
+    pattern.try_set_prefab((12, 12), Prefab::JumpPad).unwrap();
+    pattern.try_set_level((12, 13), 20).unwrap();
+    assert_eq!(
+        summary(&only(JumpPadUnderOverhang::default()).validate(&pattern)),
+        vec![("jump-pad-under-overhang", Severity::Warning, vec![at(5, 5)])]
+    );
+}
+
+#[test]
+fn central_spawn() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((8, 8), 1).unwrap();
+    assert_eq!(
+        summary(&only(CentralSpawn::default()).validate(&pattern)),
+        vec![(
+            "central-spawn",
+            Severity::Error,
+            vec![at(7, 7), at(8, 7), at(7, 8), at(8, 8)]
+        )]
+    );
+
+    // a raised but flat spawn is fine, a prefab on it is not
+    let mut pattern = MapPattern::default();
+    for (x, y) in [(7, 7), (8, 7), (7, 8), (8, 8)] {
+        pattern.try_set_level((x, y), 3).unwrap();
+    }
+    assert!(only(CentralSpawn::default()).validate(&pattern).is_empty());
+    pattern.try_set_prefab((7, 8), Prefab::Projectile).unwrap();
+    assert_eq!(only(CentralSpawn::default()).validate(&pattern).len(), 1);
+}
+
+#[test]
+fn wall_height() {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((0, 0), 21).unwrap();
+    pattern.try_set_level((1, 0), 20).unwrap();
+    assert_eq!(
+        summary(&only(WallHeight::default()).validate(&pattern)),
+        vec![("wall-height", Severity::Info, vec![at(0, 0)])]
+    );
+}
+
+/// a wall (info), a prefab in a pit (warning) and a blocked spawn (error)
+fn messy() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    pattern.try_set_level((0, 0), 30).unwrap();
+    pattern.try_set_level((2, 2), -1).unwrap();
+    pattern.try_set_prefab((2, 2), Prefab::Melee).unwrap();
+    pattern.try_set_prefab((8, 8), Prefab::Hideous).unwrap();
+    pattern
+}
+
+#[test]
+fn diagnostics_are_sorted_most_severe_first() {
+    let diagnostics = messy().validate();
+    let rules: Vec<(&str, Severity)> = diagnostics.iter().map(|d| (d.rule, d.severity)).collect();
+    assert_eq!(
+        rules,
+        vec![
+            ("central-spawn", Severity::Error),
+            ("prefab-in-pit", Severity::Warning),
+            ("wall-height", Severity::Info),
+        ]
+    );
+    assert_eq!(diagnostics, Validator::default().validate(&messy()));
+}
+
+#[test]
+fn disabled_rules_are_silent() {
+    let mut validator = Validator::default();
+    assert!(validator.disable("wall-height"));
+    assert!(!validator.is_enabled("wall-height"));
+    assert!(validator.rules().any(|name| name == "wall-height"));
+    assert!(validator
+        .validate(&messy())
+        .iter()
+        .all(|d| d.rule != "wall-height"));
+
+    assert!(validator.enable("wall-height"));
+    assert!(validator
+        .validate(&messy())
+        .iter()
+        .any(|d| d.rule == "wall-height"));
+
+    assert!(!validator.disable("no-such-rule"));
+    assert!(!validator.enable("no-such-rule"));
+}
+
+struct NoHideous;
+
+impl Rule for NoHideous {
+    fn name(&self) -> &'static str {
+        "no-hideous"
+    }
+
+    fn check(&self, pattern: &MapPattern) -> Vec<Diagnostic> {
+        let cells: Vec<Coord> = Coord::all()
+            .filter(|at| pattern.get_prefab(*at) == Prefab::Hideous)
+            .collect();
+        if cells.is_empty() {
+            return Vec::new();
+        }
+        vec![Diagnostic {
+            rule: self.name(),
+            severity: Severity::Warning,
+            message: "hideous prefabs are not allowed".to_string(),
+            cells,
+        }]
+    }
+}
+
+#[test]
+fn custom_rules_run() {
+    let mut validator = Validator::default();
+    validator.register(NoHideous);
+    let diagnostics = validator.validate(&messy());
+    let custom: Vec<&Diagnostic> = diagnostics
+        .iter()
+        .filter(|d| d.rule == "no-hideous")
+        .collect();
+    assert_eq!(custom.len(), 1);
+    assert_eq!(custom[0].cells, vec![at(8, 8)]);
+    assert_eq!(
+        custom[0].to_string(),
+        "warning [no-hideous]: hideous prefabs are not allowed"
+    );
+}
+
+#[test]
+fn registering_a_rule_with_the_same_name_replaces_it() {
+    let mut validator = Validator::default();
+    validator.register(WallHeight {
+        max: Height::new(40).unwrap(),
+    });
+    assert_eq!(validator.rules().count(), 6);
+    assert!(validator
+        .validate(&messy())
+        .iter()
+        .all(|d| d.rule != "wall-height"));
+}
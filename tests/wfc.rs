@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use ultra_map_lib::generate::{PatternGenerator, WfcGenerator};
+use ultra_map_lib::{Coord, Error, Height, MapPattern, Paint, Prefab, GRID_SIZE};
+
+/// 4x4 blocks of height 0 or 2 in an irregular layout, with a jump pad in the middle of some
+fn blocks() -> MapPattern {
+    let mut pattern = MapPattern::default();
+    for at in Coord::all() {
+        let block = (at.x() / 4) * 5 + (at.y() / 4) * 3;
+        let raised = block % 3 == 0;
+        pattern[at] = Height::new(if raised { 2 } else { 0 }).unwrap();
+        if raised && at.x() % 4 == 1 && at.y() % 4 == 1 {
+            pattern.try_set_prefab(at, Prefab::JumpPad).unwrap();
+        }
+    }
+    pattern
+}
+
+fn windows(pattern: &MapPattern, size: usize) -> HashSet<Vec<(Height, Prefab)>> {
+    let mut windows = HashSet::new();
+    for y in 0..=GRID_SIZE - size {
+        for x in 0..=GRID_SIZE - size {
+            let mut window = Vec::new();
+            for dy in 0..size {
+                for dx in 0..size {
+                    let at = Coord::new(x + dx, y + dy).unwrap();
+                    window.push((pattern.get_level(at), pattern.get_prefab(at)));
+                }
+            }
+            windows.insert(window);
+        }
+    }
+    windows
+}
+
+fn generator(size: usize) -> WfcGenerator {
+    let mut generator = WfcGenerator::new(&[blocks()], size).unwrap();
+    generator.max_attempts = 50;
+    generator
+}
+
+#[test]
+fn output_windows_are_learned_tiles() {
+    for size in [2, 3] {
+        let generator = generator(size);
+        let learned = windows(&blocks(), size);
+        assert_eq!(generator.tile_count(), learned.len());
+
+        for seed in 0..5 {
+            let pattern = generator.generate(seed).unwrap();
+            assert!(windows(&pattern, size).is_subset(&learned));
+        }
+    }
+}
+
+#[test]
+fn same_seed_gives_the_same_pattern() {
+    let generator = generator(2);
+    assert_eq!(
+        generator.generate(7).unwrap(),
+        generator.generate(7).unwrap()
+    );
+}
+
+#[test]
+fn pins_are_respected() {
+    let mut generator = generator(2);
+    let raised = Coord::new(9, 3).unwrap();
+    let pad = Coord::new(13, 13).unwrap();
+    let flat = Coord::new(0, 15).unwrap();
+    generator.pin(raised, Paint::height(Height::new(2).unwrap()));
+    generator.pin(pad, Paint::prefab(Prefab::JumpPad));
+    generator.pin(flat, Paint::both(Height::ZERO, Prefab::Empty));
+
+    for seed in 0..5 {
+        let pattern = generator.generate(seed).unwrap();
+        assert_eq!(pattern.get_level(raised).get(), 2);
+        assert_eq!(pattern.get_prefab(pad), Prefab::JumpPad);
+        assert_eq!(pattern.get_level(flat), Height::ZERO);
+        assert_eq!(pattern.get_prefab(flat), Prefab::Empty);
+    }
+}
+
+#[test]
+fn impossible_pins_report_a_contradiction() {
+    let mut generator = generator(2);
+    generator.max_attempts = 3;
+    let at = Coord::new(4, 4).unwrap();
+    generator.pin(at, Paint::height(Height::new(7).unwrap()));
+
+    match generator.generate(0) {
+        Err(Error::UltraMapContradiction(_)) => {}
+        other => panic!("expected a contradiction, got {:?}", other),
+    }
+}
+
+#[test]
+fn conflicting_pins_report_a_contradiction() {
+    let mut generator = generator(2);
+    // jump pads never touch each other in the example
+    generator.pin(Coord::new(5, 5).unwrap(), Paint::prefab(Prefab::JumpPad));
+    generator.pin(Coord::new(6, 5).unwrap(), Paint::prefab(Prefab::JumpPad));
+
+    assert!(matches!(
+        generator.generate(1),
+        Err(Error::UltraMapContradiction(_))
+    ));
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    assert!(WfcGenerator::new(&[], 2).is_err());
+    assert!(WfcGenerator::new(&[blocks()], 0).is_err());
+    assert!(WfcGenerator::new(&[blocks()], 17).is_err());
+}